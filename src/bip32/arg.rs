@@ -1,14 +1,37 @@
-use artimonist::bitcoin::bip32::DerivationPath;
+use super::pattern::PathPattern;
+use crate::derive::parse_seed;
+use crate::utils::default_jobs;
+use artimonist::bitcoin::{Network, PrivateKey};
 use artimonist::{Mnemonic, Xpriv, Xpub};
 
 #[derive(clap::Parser, Debug)]
 pub struct Bip32Command {
-    /// Mnemonic phrase or Master key
+    /// Mnemonic phrase, Master key, seed hex or WIF private key
     #[clap(name = "MNEMONIC|MASTER KEY")]
     pub key: MasterKey,
 
-    /// Derivation path
-    pub path: Option<DerivationPath>,
+    /// Derivation path, with ranges and wildcards: m/84'/0'/0'/0/0..100, m/84'/0'/0'/{0,1}/*
+    pub path: Option<PathPattern>,
+
+    /// Wildcard start index
+    #[clap(short, long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..2147483648))]
+    pub index: u32,
+
+    /// Amount of wildcard indices
+    #[clap(short = 'm', long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(0..65536))]
+    pub amount: u32,
+
+    /// Fields to print, one line per path [default: all fields of a single path, addresses of ranges]
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub show: Vec<Field>,
+
+    /// Network of addresses: bitcoin, testnet, signet, regtest [default: by key version]
+    #[clap(long)]
+    pub network: Option<Network>,
+
+    /// Amount of worker threads [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
 
     /// Password as salt
     #[clap(hide = true, long)]
@@ -20,26 +43,66 @@ pub struct Bip32Command {
 pub enum MasterKey {
     /// Mnemonic phrase
     Mnemonic(Mnemonic),
-    /// Extended private key
+    /// Extended private key, or from seed hex
     Xpriv(Xpriv),
     /// Extended public key
     Xpub(Xpub),
+    /// Single private key in wif format
+    Wif(PrivateKey),
 }
 
 impl std::str::FromStr for MasterKey {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("xprv") {
+        if s.starts_with("xprv") || s.starts_with("tprv") {
             Ok(MasterKey::Xpriv(Xpriv::from_str(s)?))
-        } else if s.starts_with("xpub") {
+        } else if s.starts_with("xpub") || s.starts_with("tpub") {
             Ok(MasterKey::Xpub(Xpub::from_str(s)?))
+        } else if let Some(seed) = parse_seed(s) {
+            Ok(MasterKey::Xpriv(Xpriv::new_master(
+                artimonist::NETWORK,
+                &seed,
+            )?))
+        } else if let Ok(wif) = PrivateKey::from_wif(s) {
+            Ok(MasterKey::Wif(wif))
         } else {
             Ok(MasterKey::Mnemonic(Mnemonic::from_str(s)?))
         }
     }
 }
 
+/// Output field of a derived key
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// Extended private key
+    Xprv,
+    /// Extended public key
+    Xpub,
+    /// Private key, bip38 encrypted by password
+    Bip38,
+    /// Public key
+    Pubkey,
+    /// Legacy address
+    P2pkh,
+    /// Nested segwit address
+    P2shWpkh,
+    /// Native segwit address
+    P2wpkh,
+    /// Taproot key path address
+    P2tr,
+}
+
+impl Field {
+    /// Fields of ranges by default
+    pub const ADDRESSES: [Field; 4] = [Field::P2pkh, Field::P2shWpkh, Field::P2wpkh, Field::P2tr];
+
+    #[inline(always)]
+    pub fn is_private(self) -> bool {
+        matches!(self, Field::Xprv | Field::Bip38)
+    }
+}
+
 impl Bip32Command {
     pub fn is_mnemonic(&self) -> bool {
         matches!(self.key, MasterKey::Mnemonic(_))
@@ -47,22 +110,27 @@ impl Bip32Command {
     pub fn is_xpub(&self) -> bool {
         matches!(self.key, MasterKey::Xpub(_))
     }
+
+    #[inline]
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(default_jobs)
+    }
 }
 
-pub fn inquire_derive_path(is_xpub: bool) -> anyhow::Result<DerivationPath> {
+pub fn inquire_derive_path(is_xpub: bool) -> anyhow::Result<PathPattern> {
     use inquire::Text;
     use std::str::FromStr;
 
     let path = if is_xpub {
         Text::new("Enter derivation path: ")
             .with_initial_value("m/0/0")
-            .with_help_message("e.g. m/0/0 or m/0/2147483647 ")
+            .with_help_message("e.g. m/0/0, m/0/2147483647 or m/0/0..100 ")
             .prompt()?
     } else {
         Text::new("Enter derivation path: ")
             .with_initial_value("m/0'/0'")
-            .with_help_message("e.g. m/0'/0' or m/0'/2147483647' ")
+            .with_help_message("e.g. m/0'/0', m/0'/2147483647' or m/0'/{0,1}/* ")
             .prompt()?
     };
-    Ok(DerivationPath::from_str(&path)?)
+    PathPattern::from_str(&path)
 }
@@ -1,12 +1,31 @@
-use super::arg::{MasterKey, inquire_derive_path};
-use crate::{Execute, utils::inquire_password};
-use artimonist::bitcoin::{self, Address, bip32::DerivationPath};
-use artimonist::{BIP38, Xpriv, Xpub};
+use super::arg::{Field, MasterKey, inquire_derive_path};
+use crate::Execute;
+use crate::derive::{fingerprint_label, key_origin};
+use crate::encrypt::encrypt_key;
+use crate::utils::{inquire_password, ordered_for_each};
+use anyhow::anyhow;
+use artimonist::bitcoin::secp256k1::{All, Secp256k1};
+use artimonist::bitcoin::{
+    Address, CompressedPublicKey, Network, PrivateKey, bip32::DerivationPath,
+};
+use artimonist::{Xpriv, Xpub};
 use std::io::Write;
+use std::sync::LazyLock;
+
+static SECP: LazyLock<Secp256k1<All>> = LazyLock::new(Secp256k1::new);
 
 impl Execute for super::arg::Bip32Command {
     fn execute(&mut self) -> anyhow::Result<()> {
-        let path = match &self.path {
+        // single private key has no derivation
+        if let MasterKey::Wif(wif) = &self.key {
+            let password = match &self.password {
+                Some(p) => p.clone(),
+                None => inquire_password(false)?,
+            };
+            return show_wif(wif, &password, self.network);
+        }
+
+        let pattern = match &self.path {
             Some(p) => p.clone(),
             None => inquire_derive_path(self.is_xpub())?,
         };
@@ -17,58 +36,199 @@ impl Execute for super::arg::Bip32Command {
             (true, _) => String::new(), // Xpub does not require a password
         };
 
-        match &self.key {
-            MasterKey::Mnemonic(mnemonic) => {
-                let master = mnemonic.to_master(&password)?;
-                derive_xprv(&master, &path, &password)
-            }
-            MasterKey::Xpriv(xprv) => derive_xprv(xprv, &path, &password),
-            MasterKey::Xpub(xpub) => derive_xpub(xpub, &path),
+        let root = match &self.key {
+            MasterKey::Mnemonic(mnemonic) => MasterKey::Xpriv(mnemonic.to_master(&password)?),
+            key => key.clone(),
+        };
+
+        // addresses of the key version network, unless specified
+        let version = match &root {
+            MasterKey::Xpriv(xprv) => xprv.network,
+            MasterKey::Xpub(xpub) => xpub.network,
+            MasterKey::Mnemonic(_) | MasterKey::Wif(_) => unreachable!(),
+        };
+        let network = self.network.unwrap_or(match version.is_mainnet() {
+            true => Network::Bitcoin,
+            false => Network::Testnet,
+        });
+
+        // a single path prints all details
+        if pattern.is_single() && self.show.is_empty() {
+            let path = pattern.paths(0, 0)?.remove(0);
+            return match &root {
+                MasterKey::Xpriv(xprv) => derive_xprv(xprv, &path, &password, network),
+                MasterKey::Xpub(xpub) => derive_xpub(xpub, &path, network),
+                MasterKey::Mnemonic(_) | MasterKey::Wif(_) => unreachable!(),
+            };
+        }
+
+        let fields = match self.show.is_empty() {
+            true => Field::ADDRESSES.to_vec(),
+            false => self.show.clone(),
+        };
+        if self.is_xpub() && fields.iter().any(|f| f.is_private()) {
+            return Err(anyhow!("Xpub can not derive private keys"));
         }
+        let paths = pattern.paths(self.index, self.amount)?;
+        let mut f = std::io::stdout();
+        ordered_for_each(
+            &paths,
+            self.jobs(),
+            |path| derive_fields(&root, path, &fields, &password, network),
+            |path, values| Ok(writeln!(f, "[m/{path}]: {}", values.join(", "))?),
+        )
     }
 }
 
-fn derive_xprv(master: &Xpriv, path: &DerivationPath, password: &str) -> anyhow::Result<()> {
+/// Selected fields of the key at path
+fn derive_fields(
+    root: &MasterKey,
+    path: &DerivationPath,
+    fields: &[Field],
+    password: &str,
+    network: Network,
+) -> anyhow::Result<Vec<String>> {
+    let (xprv, xpub) = match root {
+        MasterKey::Xpriv(master) => {
+            let xprv = master.derive_priv(&SECP, path)?;
+            (Some(xprv), Xpub::from_priv(&SECP, &xprv))
+        }
+        MasterKey::Xpub(master) => (None, master.derive_pub(&SECP, path)?),
+        MasterKey::Mnemonic(_) | MasterKey::Wif(_) => unreachable!(),
+    };
+    let pub_key = xpub.to_pub();
+    let private = || xprv.ok_or(anyhow!("Xpub can not derive private keys"));
+    fields
+        .iter()
+        .map(|field| {
+            Ok(match field {
+                Field::Xprv => private()?.to_string(),
+                Field::Bip38 => encrypt_key(&private()?.to_priv().to_wif(), password, false)?,
+                Field::Xpub => xpub.to_string(),
+                Field::Pubkey => pub_key.to_string(),
+                Field::P2pkh => to_address(AddressType::P2pkh, &pub_key, network).to_string(),
+                Field::P2shWpkh => to_address(AddressType::P2shWpkh, &pub_key, network).to_string(),
+                Field::P2wpkh => to_address(AddressType::P2wpkh, &pub_key, network).to_string(),
+                Field::P2tr => to_address(AddressType::P2tr, &pub_key, network).to_string(),
+            })
+        })
+        .collect()
+}
+
+fn derive_xprv(
+    master: &Xpriv,
+    path: &DerivationPath,
+    password: &str,
+    network: Network,
+) -> anyhow::Result<()> {
     let mut f = std::io::BufWriter::new(std::io::stdout());
     writeln!(f, "Master key: {master}")?;
     writeln!(f, "Derivation path: [m/{path}]")?;
 
-    let secp = bitcoin::secp256k1::Secp256k1::default();
-    let xprv = master.derive_priv(&secp, &path)?;
-    let xpub = Xpub::from_priv(&secp, &xprv);
+    let xprv = master.derive_priv(&SECP, &path)?;
+    let xpub = Xpub::from_priv(&SECP, &xprv);
+    let fingerprint = master.fingerprint(&SECP);
+    writeln!(f, "{}: {fingerprint}", fingerprint_label(master.depth))?;
+    writeln!(f, "Parent fingerprint: {}", xprv.parent_fingerprint)?;
+    if master.depth == 0 {
+        let origin = key_origin(fingerprint, &format!("m/{path}"));
+        writeln!(f, "Key origin: {origin}")?;
+    }
     writeln!(f, "Extended private key: {xprv}")?;
     writeln!(f, "Extended public key: {xpub}")?;
 
     let (pub_key, priv_wif) = (xpub.to_pub(), xprv.to_priv().to_string());
-    writeln!(f, "Private key: {}", priv_wif.bip38_encrypt(password)?)?;
+    writeln!(
+        f,
+        "Private key: {}",
+        encrypt_key(&priv_wif, password, false)?
+    )?;
     writeln!(f, "Public key: {pub_key}")?;
 
-    let network = artimonist::bitcoin::Network::Bitcoin;
     writeln!(f, "Addresses: ")?;
-    writeln!(f, "  P2PKH: {}", Address::p2pkh(pub_key, network))?;
-    writeln!(f, "  P2SH-WPKH: {}", Address::p2shwpkh(&pub_key, network))?;
-    writeln!(f, "  P2WPKH: {}", Address::p2wpkh(&pub_key, network))?;
+    for (label, kind) in ADDRESS_LABELS {
+        writeln!(f, "  {label}: {}", to_address(kind, &pub_key, network))?;
+    }
 
     Ok(())
 }
 
-fn derive_xpub(master: &Xpub, path: &DerivationPath) -> anyhow::Result<()> {
+fn derive_xpub(master: &Xpub, path: &DerivationPath, network: Network) -> anyhow::Result<()> {
     let mut f = std::io::BufWriter::new(std::io::stdout());
     writeln!(f, "Root key: {master}")?;
     writeln!(f, "Derivation path: [m/{path}]")?;
 
-    let secp = bitcoin::secp256k1::Secp256k1::default();
-    let xpub = master.derive_pub(&secp, &path)?;
+    let xpub = master.derive_pub(&SECP, &path)?;
+    let fingerprint = master.fingerprint();
+    writeln!(f, "{}: {fingerprint}", fingerprint_label(master.depth))?;
+    writeln!(f, "Parent fingerprint: {}", xpub.parent_fingerprint)?;
+    if master.depth == 0 {
+        let origin = key_origin(fingerprint, &format!("m/{path}"));
+        writeln!(f, "Key origin: {origin}")?;
+    }
     writeln!(f, "Extended public key: {xpub}")?;
 
     let pub_key = xpub.to_pub();
     writeln!(f, "Public key: {pub_key}")?;
 
-    let network = artimonist::bitcoin::Network::Bitcoin;
     writeln!(f, "Addresses: ")?;
-    writeln!(f, "  P2PKH: {}", Address::p2pkh(pub_key, network))?;
-    writeln!(f, "  P2SH-WPKH: {}", Address::p2shwpkh(&pub_key, network))?;
-    writeln!(f, "  P2WPKH: {}", Address::p2wpkh(&pub_key, network))?;
+    for (label, kind) in ADDRESS_LABELS {
+        writeln!(f, "  {label}: {}", to_address(kind, &pub_key, network))?;
+    }
 
     Ok(())
 }
+
+/// Public key, addresses and bip38 encrypted form of a single private key,
+///   addresses of the key network unless specified
+pub fn show_wif(wif: &PrivateKey, password: &str, network: Option<Network>) -> anyhow::Result<()> {
+    let network = network.unwrap_or(match wif.network.is_mainnet() {
+        true => Network::Bitcoin,
+        false => Network::Testnet,
+    });
+    let pub_key = wif.public_key(&SECP);
+
+    let mut f = std::io::BufWriter::new(std::io::stdout());
+    writeln!(
+        f,
+        "Private key: {}",
+        encrypt_key(&wif.to_wif(), password, false)?
+    )?;
+    writeln!(f, "Public key: {pub_key}")?;
+    writeln!(f, "Addresses: ")?;
+    match CompressedPublicKey::try_from(pub_key) {
+        Ok(pub_key) => {
+            for (label, kind) in ADDRESS_LABELS {
+                writeln!(f, "  {label}: {}", to_address(kind, &pub_key, network))?;
+            }
+        }
+        // segwit requires compressed public key
+        Err(_) => writeln!(f, "  P2PKH: {}", Address::p2pkh(pub_key, network))?,
+    }
+    Ok(())
+}
+
+/// Single key address types
+#[derive(Clone, Copy)]
+enum AddressType {
+    P2pkh,
+    P2shWpkh,
+    P2wpkh,
+    P2tr,
+}
+
+const ADDRESS_LABELS: [(&str, AddressType); 4] = [
+    ("P2PKH", AddressType::P2pkh),
+    ("P2SH-WPKH", AddressType::P2shWpkh),
+    ("P2WPKH", AddressType::P2wpkh),
+    ("P2TR", AddressType::P2tr),
+];
+
+fn to_address(kind: AddressType, pub_key: &CompressedPublicKey, network: Network) -> Address {
+    match kind {
+        AddressType::P2pkh => Address::p2pkh(pub_key, network),
+        AddressType::P2shWpkh => Address::p2shwpkh(pub_key, network),
+        AddressType::P2wpkh => Address::p2wpkh(pub_key, network),
+        AddressType::P2tr => Address::p2tr(&SECP, pub_key.0.into(), None, network),
+    }
+}
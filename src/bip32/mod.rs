@@ -1,4 +1,6 @@
 mod arg;
 mod execute;
+mod pattern;
 
 pub use arg::Bip32Command;
+pub use execute::show_wif;
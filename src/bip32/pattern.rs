@@ -0,0 +1,148 @@
+use anyhow::anyhow;
+use artimonist::bitcoin::bip32::{ChildNumber, DerivationPath};
+
+/// Maximum amount of paths a pattern expands to
+const MAX_PATHS: usize = 1 << 20;
+
+/// Derivation path with ranges and wildcards,
+///   e.g. `m/84'/0'/0'/0/0..100`, `m/84'/0'/0'/{0,1}/*`
+#[derive(Debug, Clone)]
+pub struct PathPattern(Vec<Step>);
+
+/// One level of the path pattern
+#[derive(Debug, Clone)]
+enum Step {
+    /// Single index: `5`, `5'`
+    Index(u32, bool),
+    /// Index range: `0..100` (end excluded), `0..=99` (end included)
+    Range(u32, u32, bool),
+    /// Index set: `{0,1}`, `{0',5'}`
+    Set(Vec<(u32, bool)>),
+    /// Indices from `--index`, total `--amount`: `*`, `*'`
+    Wildcard(bool),
+}
+
+impl std::str::FromStr for PathPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("m").unwrap_or(s);
+        let s = s.strip_prefix("/").unwrap_or(s);
+        if s.is_empty() {
+            return Ok(PathPattern(vec![]));
+        }
+        let steps = s
+            .split('/')
+            .map(|step| parse_step(step).ok_or(anyhow!("Invalid derivation path: {step}")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(PathPattern(steps))
+    }
+}
+
+/// Index with optional hardened mark: `5`, `5'`, `5h`
+fn parse_index(s: &str) -> Option<(u32, bool)> {
+    let (s, hardened) = match s.strip_suffix(['\'', 'h', 'H']) {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let index = s.parse::<u32>().ok().filter(|&i| i < (1 << 31))?;
+    Some((index, hardened))
+}
+
+fn parse_step(s: &str) -> Option<Step> {
+    if let Some(set) = s.strip_prefix('{') {
+        // a hardened mark after the braces applies to all indices
+        let (set, hardened) = match set.strip_suffix('}') {
+            Some(set) => (set, false),
+            None => (set.strip_suffix(['\'', 'h', 'H'])?.strip_suffix('}')?, true),
+        };
+        let indices = set
+            .split(',')
+            .map(|i| parse_index(i.trim()).map(|(i, h)| (i, h || hardened)))
+            .collect::<Option<Vec<_>>>()?;
+        return Some(Step::Set(indices));
+    }
+
+    let (s, hardened) = match s.strip_suffix(['\'', 'h', 'H']) {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    if s == "*" {
+        Some(Step::Wildcard(hardened))
+    } else if let Some((start, end)) = s.split_once("..=") {
+        let (start, end) = (parse_index(start)?, parse_index(end)?);
+        (!start.1 && !end.1).then_some(Step::Range(start.0, end.0.checked_add(1)?, hardened))
+    } else if let Some((start, end)) = s.split_once("..") {
+        let (start, end) = (parse_index(start)?, parse_index(end)?);
+        (!start.1 && !end.1).then_some(Step::Range(start.0, end.0, hardened))
+    } else {
+        parse_index(s)
+            .filter(|&(_, h)| !h)
+            .map(|(i, _)| Step::Index(i, hardened))
+    }
+}
+
+impl Step {
+    /// Amount of indices at this level
+    fn len(&self, amount: u32) -> usize {
+        match self {
+            Step::Index(..) => 1,
+            Step::Range(start, end, _) => end.saturating_sub(*start) as usize,
+            Step::Set(set) => set.len(),
+            Step::Wildcard(_) => amount as usize,
+        }
+    }
+
+    fn children(&self, index: u32, amount: u32) -> anyhow::Result<Vec<ChildNumber>> {
+        let indices = match self {
+            Step::Index(i, h) => vec![(*i, *h)],
+            Step::Range(start, end, h) => (*start..*end).map(|i| (i, *h)).collect(),
+            Step::Set(set) => set.clone(),
+            Step::Wildcard(h) => (index..index.saturating_add(amount))
+                .map(|i| (i, *h))
+                .collect(),
+        };
+        let children = indices
+            .into_iter()
+            .map(|(i, h)| match h {
+                true => ChildNumber::from_hardened_idx(i),
+                false => ChildNumber::from_normal_idx(i),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(children)
+    }
+}
+
+impl PathPattern {
+    /// Pattern without ranges or wildcards
+    pub fn is_single(&self) -> bool {
+        self.0.iter().all(|step| matches!(step, Step::Index(..)))
+    }
+
+    /// Expand to derivation paths, wildcards take `amount` indices from `index`
+    pub fn paths(&self, index: u32, amount: u32) -> anyhow::Result<Vec<DerivationPath>> {
+        self.0
+            .iter()
+            .try_fold(1usize, |n, step| n.checked_mul(step.len(amount)))
+            .filter(|&n| n <= MAX_PATHS)
+            .ok_or(anyhow!("Too many derivation paths, at most {MAX_PATHS}"))?;
+
+        // cartesian product, the last level changes fastest
+        let mut paths = vec![vec![]];
+        for step in &self.0 {
+            let children = step.children(index, amount)?;
+            paths = paths
+                .into_iter()
+                .flat_map(|path: Vec<ChildNumber>| {
+                    children.iter().map(move |&child| {
+                        let mut path = path.clone();
+                        path.push(child);
+                        path
+                    })
+                })
+                .collect();
+        }
+        Ok(paths.into_iter().map(DerivationPath::from).collect())
+    }
+}
@@ -1,8 +1,10 @@
+use crate::utils::default_jobs;
+use artimonist::bitcoin::{PrivateKey, hex::FromHex};
 use artimonist::{Mnemonic, Xpriv};
 
 #[derive(clap::Parser, Debug)]
 pub struct DeriveCommand {
-    /// Mnemonic phrase or Master key
+    /// Mnemonic phrase, Master key, seed hex or WIF private key
     #[clap(name = "MNEMONIC|MASTER KEY")]
     pub key: MasterKey,
 
@@ -18,6 +20,10 @@ pub struct DeriveCommand {
     #[clap(short = 'm', long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(0..65536))]
     pub amount: u32,
 
+    /// Amount of worker threads [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
+
     /// Derivation path select
     #[clap(flatten)]
     pub derive: DerivePath,
@@ -30,6 +36,34 @@ pub struct DeriveCommand {
     #[clap(long)]
     pub detail: bool,
 
+    /// Script type of multisig [default: by derive path]
+    #[clap(long, value_enum, requires = "MultiSig")]
+    pub script: Option<MultisigScript>,
+
+    /// Sort multisig public keys, as descriptor sortedmulti [default]
+    #[clap(long, requires = "MultiSig", conflicts_with = "unsorted")]
+    pub sorted: bool,
+
+    /// Keep multisig public keys in account order, as descriptor multi
+    #[clap(long, requires = "MultiSig")]
+    pub unsorted: bool,
+
+    /// Output layout of multisig wallets
+    #[clap(long, value_enum, default_value_t = Layout::Table)]
+    pub layout: Layout,
+
+    /// Rows per table page of multisig wallets, 0 for a single table
+    #[clap(long, default_value_t = 0)]
+    pub page: usize,
+
+    /// Export multisig quorum to Coldcard config file
+    #[clap(long, value_name = "FILE", requires = "MultiSig")]
+    pub coldcard: Option<String>,
+
+    /// Export multisig quorum to BSMS (BIP129) descriptor record file
+    #[clap(long, value_name = "FILE", requires = "MultiSig")]
+    pub bsms: Option<String>,
+
     /// Password as salt
     #[clap(hide = true, long)]
     pub password: Option<String>,
@@ -38,24 +72,41 @@ pub struct DeriveCommand {
 /// Master key or Mnemonic string
 #[derive(Debug, Clone)]
 pub enum MasterKey {
-    /// Master key in xprv format
+    /// Master key in xprv format, or from seed hex
     Xpriv(Xpriv),
     /// Mnemonic phrase
     Mnemonic(Mnemonic),
+    /// Single private key in wif format
+    Wif(PrivateKey),
 }
 
 impl std::str::FromStr for MasterKey {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("xprv") {
+        if s.starts_with("xprv") || s.starts_with("tprv") {
             Ok(MasterKey::Xpriv(Xpriv::from_str(s)?))
+        } else if let Some(seed) = parse_seed(s) {
+            Ok(MasterKey::Xpriv(Xpriv::new_master(
+                artimonist::NETWORK,
+                &seed,
+            )?))
+        } else if let Ok(wif) = PrivateKey::from_wif(s) {
+            Ok(MasterKey::Wif(wif))
         } else {
             Ok(MasterKey::Mnemonic(Mnemonic::from_str(s)?))
         }
     }
 }
 
+/// BIP32 seed in hex, 16 ~ 64 bytes
+pub fn parse_seed(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !(32..=128).contains(&s.len()) {
+        return None;
+    }
+    Vec::from_hex(s).ok()
+}
+
 #[derive(clap::Args, Debug)]
 #[group(required = false, multiple = false)]
 pub struct DerivePath {
@@ -68,6 +119,12 @@ pub struct DerivePath {
     /// Use derive path: m/84'/0'/account'/0/index [p2wpkh]
     #[clap(long)]
     pub bip84: bool,
+    /// Use multisig derive path: m/48'/0'/account'/2'/0/index [p2wsh]
+    #[clap(long, requires = "MultiSig")]
+    pub bip48: bool,
+    /// Use multisig derive path: m/48'/0'/account'/1'/0/index [p2shwsh]
+    #[clap(long, requires = "MultiSig")]
+    pub bip48_nested: bool,
 }
 
 #[derive(clap::Args, Debug)]
@@ -82,14 +139,52 @@ pub struct MultiSig {
     pub m35: bool,
 }
 
+/// Multisig script type
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultisigScript {
+    /// Legacy p2sh
+    P2sh,
+    /// Nested segwit p2wsh in p2sh
+    P2shP2wsh,
+    /// Native segwit p2wsh
+    P2wsh,
+    /// Taproot script path with unspendable internal key
+    P2tr,
+}
+
+/// Multisig wallets output layout
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Address table, printed by pages
+    Table,
+    /// One line per address, printed as derived
+    Stream,
+}
+
 impl DeriveCommand {
     #[inline(always)]
     pub fn is_mnemonic(&self) -> bool {
         matches!(self.key, MasterKey::Mnemonic(_))
     }
 
+    #[inline(always)]
+    pub fn is_wif(&self) -> bool {
+        matches!(self.key, MasterKey::Wif(_))
+    }
+
     #[inline(always)]
     pub fn is_multisig(&self) -> bool {
         self.multisig.m23 || self.multisig.m35
     }
+
+    /// Multisig keys are sorted by `--sorted` or by default, unless `--unsorted`
+    #[inline(always)]
+    pub fn is_sorted(&self) -> bool {
+        self.sorted || !self.unsorted
+    }
+
+    #[inline]
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(default_jobs)
+    }
 }
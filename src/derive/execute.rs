@@ -1,10 +1,19 @@
+use super::path::{fingerprint_label, key_origin};
 use super::{DeriveCommand, arg::MasterKey, multisig::MultiSig};
-use crate::{Execute, utils::inquire_password};
-use artimonist::{BIP38, Xpriv};
+use crate::Execute;
+use crate::bip32::show_wif;
+use crate::encrypt::encrypt_key;
+use crate::utils::{inquire_password, ordered_for_each};
+use anyhow::anyhow;
+use artimonist::Xpriv;
+use artimonist::bitcoin::secp256k1::Secp256k1;
 use std::io::{BufWriter, Write};
 
 impl Execute for DeriveCommand {
     fn execute(&mut self) -> anyhow::Result<()> {
+        if self.is_wif() && self.is_multisig() {
+            return Err(anyhow!("Multisig requires a mnemonic or master key"));
+        }
         let password = match &self.password {
             Some(p) => p.to_string(),
             None => inquire_password(self.is_mnemonic())?,
@@ -26,6 +35,7 @@ impl Execute for DeriveCommand {
                     self.derive_wallets(&master, &password)?
                 }
             }
+            MasterKey::Wif(wif) => show_wif(wif, &password, None)?,
         }
         Ok(())
     }
@@ -39,26 +49,43 @@ impl Wallet for DeriveCommand {
     fn derive_wallets(&self, master: &Xpriv, password: &str) -> anyhow::Result<()> {
         assert!(!self.is_multisig());
 
+        // derive account key once
+        let account = self.derive.account_key(master, self.account)?;
+
         if self.detail {
             // derive account
-            let (xpub, xprv) = self.derive.account(master, self.account)?;
+            let (xpub, xprv) = self.derive.account(&account);
+            let fingerprint = master.fingerprint(&Secp256k1::signing_only());
             let path = self.derive.path(self.account);
+            let origin = match master.depth {
+                0 => key_origin(fingerprint, &path),
+                _ => format!("[{path}]"),
+            };
 
             // output account
             let mut f = BufWriter::new(std::io::stdout());
             writeln!(f, "account:")?;
-            writeln!(f, "[{path}]: {xpub}")?;
-            writeln!(f, "[{path}]: {xprv}")?;
+            writeln!(f, "{}: {fingerprint}", fingerprint_label(master.depth))?;
+            writeln!(f, "Parent fingerprint: {}", account.parent_fingerprint)?;
+            writeln!(f, "{origin}: {xpub}")?;
+            writeln!(f, "{origin}: {xprv}")?;
             writeln!(f, "wallets:")?;
         }
 
         // derive wallets
         let mut f = BufWriter::new(std::io::stdout());
-        for index in self.index..self.index + self.amount {
-            let (addr, pk) = self.derive.wallet(master, self.account, index)?;
-            let path = format!("{}/0/{index}", self.derive.path(self.account));
-            writeln!(f, "[{path}]: {addr}, {}", pk.bip38_encrypt(password)?)?;
-        }
-        Ok(())
+        let indices = (self.index..self.index + self.amount).collect::<Vec<_>>();
+        ordered_for_each(
+            &indices,
+            self.jobs(),
+            |&index| {
+                let (addr, pk) = self.derive.wallet(&account, index)?;
+                Ok((addr, encrypt_key(&pk, password, false)?))
+            },
+            |index, (addr, pk)| {
+                let path = format!("{}/0/{index}", self.derive.path(self.account));
+                Ok(writeln!(f, "[{path}]: {addr}, {pk}")?)
+            },
+        )
     }
 }
@@ -0,0 +1,119 @@
+use super::arg::MultisigScript;
+use super::path::{NUMS_KEY, key_origin};
+use anyhow::anyhow;
+use artimonist::bitcoin::bip32::Fingerprint;
+
+/// Multisig quorum for coordinator wallet files
+pub struct Quorum {
+    /// Required signatures
+    pub m: u8,
+    /// Master key fingerprint of all accounts
+    pub fingerprint: Fingerprint,
+    /// Account (path, xpub)
+    pub accounts: Vec<(String, String)>,
+    pub script: MultisigScript,
+    pub sorted: bool,
+    /// First receive address
+    pub address: String,
+}
+
+impl Quorum {
+    fn policy(&self) -> String {
+        format!("{} of {}", self.m, self.accounts.len())
+    }
+
+    /// Coldcard multisig setup file, each account has its own derivation
+    pub fn to_coldcard(&self) -> anyhow::Result<String> {
+        let format = match self.script {
+            MultisigScript::P2sh => "P2SH",
+            MultisigScript::P2shP2wsh => "P2SH-P2WSH",
+            MultisigScript::P2wsh => "P2WSH",
+            MultisigScript::P2tr => return Err(anyhow!("Coldcard config does not support p2tr")),
+        };
+        if !self.sorted {
+            return Err(anyhow!("Coldcard config requires sorted keys"));
+        }
+
+        let mut content = String::from("# Coldcard Multisig setup file (exported by Artimonist)\n");
+        content += &format!("Name: artimonist-{}of{}\n", self.m, self.accounts.len());
+        content += &format!("Policy: {}\n", self.policy());
+        content += &format!("Format: {format}\n");
+        for (path, xpub) in &self.accounts {
+            content += &format!("\nDerivation: {path}\n");
+            content += &format!("{}: {xpub}\n", self.fingerprint.to_string().to_uppercase());
+        }
+        Ok(content)
+    }
+
+    /// BIP129 descriptor record
+    pub fn to_bsms(&self) -> String {
+        let keys = self
+            .accounts
+            .iter()
+            .map(|(path, xpub)| format!("{}{xpub}/**", key_origin(self.fingerprint, path)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let multi = match (self.script, self.sorted) {
+            (MultisigScript::P2tr, true) => "sortedmulti_a",
+            (MultisigScript::P2tr, false) => "multi_a",
+            (_, true) => "sortedmulti",
+            (_, false) => "multi",
+        };
+        let multi = format!("{multi}({},{keys})", self.m);
+        let descriptor = match self.script {
+            MultisigScript::P2sh => format!("sh({multi})"),
+            MultisigScript::P2shP2wsh => format!("sh(wsh({multi}))"),
+            MultisigScript::P2wsh => format!("wsh({multi})"),
+            MultisigScript::P2tr => {
+                let internal: String = NUMS_KEY.iter().map(|b| format!("{b:02x}")).collect();
+                format!("tr({internal},{multi})")
+            }
+        };
+        format!(
+            "BSMS 1.0\n{descriptor}#{}\n/0/*,/1/*\n{}\n",
+            descriptor_checksum(&descriptor),
+            self.address
+        )
+    }
+}
+
+/// BIP380 descriptor checksum
+fn descriptor_checksum(descriptor: &str) -> String {
+    const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+    const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const GENERATOR: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+
+    let polymod = |chk: u64, value: u64| {
+        let top = chk >> 35;
+        let chk = ((chk & 0x7ffffffff) << 5) ^ value;
+        (0..5)
+            .filter(|i| (top >> i) & 1 == 1)
+            .fold(chk, |chk, i| chk ^ GENERATOR[i])
+    };
+
+    let (mut chk, mut groups) = (1u64, vec![]);
+    for c in descriptor.chars() {
+        let v = INPUT_CHARSET.find(c).unwrap_or_default() as u64;
+        chk = polymod(chk, v & 31);
+        groups.push(v >> 5);
+        if groups.len() == 3 {
+            chk = polymod(chk, groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups[..] {
+        [g] => chk = polymod(chk, g),
+        [g0, g1] => chk = polymod(chk, g0 * 3 + g1),
+        _ => {}
+    }
+    chk = (0..8).fold(chk, |chk, _| polymod(chk, 0)) ^ 1;
+    (0..8)
+        .map(|i| CHECKSUM_CHARSET[((chk >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect()
+}
@@ -1,6 +1,8 @@
 mod arg;
 mod execute;
+mod export;
 mod multisig;
 mod path;
 
-pub use arg::DeriveCommand;
+pub use arg::{DeriveCommand, MasterKey, MultisigScript, parse_seed};
+pub use path::{DeriveMethod, fingerprint_label, key_origin, network};
@@ -1,73 +1,137 @@
+use super::arg::Layout;
+use super::export::Quorum;
+use super::path::{fingerprint_label, key_origin};
 use crate::DeriveCommand;
+use crate::utils::ordered_for_each;
 use artimonist::Xpriv;
+use artimonist::bitcoin::secp256k1::Secp256k1;
 use std::io::{BufWriter, Write};
 
 type DeriveResult<T = ()> = anyhow::Result<T>;
 
+/// Multisig wallet: (index, (address, redeem script))
+type Wallet = (u32, (String, String));
+
 pub trait MultiSig {
     fn derive_multisig(&self, master: &Xpriv) -> DeriveResult;
 
-    fn multisig_accounts(&self, accounts: &[(String, String)]) -> DeriveResult;
-    fn multisig_wallets(&self, wallets: &[(String, String)]);
-    fn multisig_scripts(&self, wallets: &[(String, String)]) -> DeriveResult;
+    fn multisig_accounts(&self, master: &Xpriv, keys: &[Xpriv]) -> DeriveResult;
+    fn multisig_wallets(&self, wallets: &[Wallet]);
+    fn multisig_scripts(&self, wallets: &[Wallet]) -> DeriveResult;
 }
 
 impl MultiSig for DeriveCommand {
     fn derive_multisig(&self, master: &Xpriv) -> DeriveResult {
         assert!(self.is_multisig());
-        let (_, n) = if self.multisig.m23 { (2, 3) } else { (3, 5) };
+        let (m, n) = if self.multisig.m23 { (2, 3) } else { (3, 5) };
 
-        // derive accounts
-        let accounts = (self.account..self.account + n)
-            .map(|account| self.derive.account(master, account))
+        // derive account keys once
+        let keys = (self.account..self.account + n)
+            .map(|account| self.derive.account_key(master, account))
             .collect::<Result<Vec<_>, _>>()?;
+        let fingerprint = master.fingerprint(&Secp256k1::signing_only());
 
-        // derive wallets
-        let wallets = (self.index..self.index + self.amount)
-            .map(|index| {
-                if self.multisig.m23 {
-                    self.derive.multisig::<2, 3>(master, self.account, index)
-                } else {
-                    self.derive.multisig::<3, 5>(master, self.account, index)
-                }
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        // export quorum for coordinators
+        if self.coldcard.is_some() || self.bsms.is_some() {
+            let script = self.derive.multisig_script(self.script);
+            let sorted = self.is_sorted();
+            let quorum = Quorum {
+                m,
+                fingerprint,
+                accounts: (self.account..)
+                    .zip(&keys)
+                    .map(|(account, key)| (self.derive.path(account), self.derive.account(key).0))
+                    .collect(),
+                script,
+                sorted,
+                address: script.wallet(m, &keys, sorted, 0)?.0,
+            };
+            if let Some(path) = &self.coldcard {
+                std::fs::write(path, quorum.to_coldcard()?)?;
+            }
+            if let Some(path) = &self.bsms {
+                std::fs::write(path, quorum.to_bsms())?;
+            }
+        }
+        self.multisig_accounts(master, &keys)?;
 
-        // output
-        self.multisig_accounts(&accounts)?;
-        self.multisig_wallets(&wallets);
-        if self.detail {
-            self.multisig_scripts(&wallets)?;
+        // derive and output wallets as they come, tables hold one page at most
+        let indices = (self.index..self.index + self.amount).collect::<Vec<_>>();
+        let mut page = Vec::with_capacity(self.page);
+        let mut f = std::io::stdout();
+        if self.layout == Layout::Stream {
+            writeln!(f)?;
+            writeln!(f, "Addresses: ")?;
+        }
+        ordered_for_each(
+            &indices,
+            self.jobs(),
+            |&index| {
+                self.derive
+                    .multisig(self.script, self.is_sorted(), m, &keys, index)
+            },
+            |&index, (addr, script)| {
+                match self.layout {
+                    Layout::Stream if self.detail => {
+                        writeln!(f, "[m/0/{index}]: {addr}, {script}")?
+                    }
+                    Layout::Stream => writeln!(f, "[m/0/{index}]: {addr}")?,
+                    Layout::Table => {
+                        page.push((index, (addr, script)));
+                        if page.len() == self.page {
+                            self.multisig_page(&page)?;
+                            page.clear();
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        if !page.is_empty() {
+            self.multisig_page(&page)?;
         }
         Ok(())
     }
 
-    fn multisig_accounts(&self, accounts: &[(String, String)]) -> DeriveResult {
+    fn multisig_accounts(&self, master: &Xpriv, keys: &[Xpriv]) -> DeriveResult {
         let mut f = BufWriter::new(std::io::stdout());
+        let fingerprint = master.fingerprint(&Secp256k1::signing_only());
         let path_first = self.derive.path(self.account);
         let path_last = self
             .derive
             .path(self.account + if self.multisig.m23 { 3 } else { 5 } - 1);
+        let origins = (self.account..)
+            .zip(keys)
+            .map(|(account, key)| {
+                let path = self.derive.path(account);
+                match master.depth {
+                    0 => (key_origin(fingerprint, &path), key),
+                    _ => (format!("[{path}]"), key),
+                }
+            })
+            .collect::<Vec<_>>();
         writeln!(f)?;
+        writeln!(f, "{}: {fingerprint}", fingerprint_label(master.depth))?;
         writeln!(f, "Account xpubs: [{path_first}] ~ [{path_last}]")?;
-        for (i, (xpub, _)) in accounts.iter().enumerate() {
-            let path = self.derive.path(self.account + i as u32);
-            writeln!(f, "[{path}]: {xpub}")?;
-            // writeln!(f, "  {xpub}")?;
+        for (origin, key) in &origins {
+            writeln!(f, "{origin}: {}", self.derive.account(key).0)?;
         }
         if self.detail {
             writeln!(f)?;
             writeln!(f, "Account xprvs: [{path_first}] ~ [{path_last}]")?;
-            for (i, (_, xpriv)) in accounts.iter().enumerate() {
-                let path = self.derive.path(self.account + i as u32);
-                writeln!(f, "[{path}]: {xpriv}")?;
-                // writeln!(f, "  {xpriv}")?;
+            for (origin, key) in &origins {
+                writeln!(f, "{origin}: {key}")?;
+            }
+            writeln!(f)?;
+            writeln!(f, "Account parent fingerprints:")?;
+            for (origin, key) in &origins {
+                writeln!(f, "{origin}: {}", key.parent_fingerprint)?;
             }
         }
         Ok(())
     }
 
-    fn multisig_wallets(&self, wallets: &[(String, String)]) {
+    fn multisig_wallets(&self, wallets: &[Wallet]) {
         use comfy_table::{ContentArrangement, Table, modifiers::*, presets::*};
         let mut table = Table::new();
         table
@@ -76,8 +140,7 @@ impl MultiSig for DeriveCommand {
             .set_content_arrangement(ContentArrangement::Dynamic)
             .set_width(100)
             .set_header(vec!["Path", "Address"]);
-        for (i, (addr, _)) in wallets.iter().enumerate() {
-            let index = self.index + i as u32;
+        for (index, (addr, _)) in wallets {
             table.add_row(vec![format!("m/0/{index}"), addr.to_string()]);
         }
         println!();
@@ -85,14 +148,24 @@ impl MultiSig for DeriveCommand {
         println!("{table}");
     }
 
-    fn multisig_scripts(&self, wallets: &[(String, String)]) -> DeriveResult {
+    fn multisig_scripts(&self, wallets: &[Wallet]) -> DeriveResult {
         let mut f = BufWriter::new(std::io::stdout());
         writeln!(f)?;
         writeln!(f, "Redeem scripts:")?;
-        for (i, (_, script)) in wallets.iter().enumerate() {
-            let index = self.index + i as u32;
+        for (index, (_, script)) in wallets {
             writeln!(f, "[m/0/{index}]: {script}")?;
         }
         Ok(())
     }
 }
+
+impl DeriveCommand {
+    /// One page of the table layout: addresses and redeem scripts
+    fn multisig_page(&self, wallets: &[Wallet]) -> DeriveResult {
+        self.multisig_wallets(wallets);
+        if self.detail {
+            self.multisig_scripts(wallets)?;
+        }
+        Ok(())
+    }
+}
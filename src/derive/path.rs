@@ -1,63 +1,258 @@
-use super::arg::DerivePath;
-use artimonist::{BIP44, BIP49, BIP84, Xpriv};
+use super::arg::{DerivePath, MultisigScript};
+use anyhow::anyhow;
+use artimonist::bitcoin::bip32::{ChildNumber, DerivationPath, Fingerprint};
+use artimonist::bitcoin::secp256k1::{All, Secp256k1, XOnlyPublicKey};
+use artimonist::bitcoin::taproot::TaprootBuilder;
+use artimonist::bitcoin::{Address, Network, PublicKey, opcodes, script::Builder};
+use artimonist::{Xpriv, Xpub};
+use std::str::FromStr;
+use std::sync::LazyLock;
 
-enum DeriveMethod {
-    Bip44 = 44,
-    Bip49 = 49,
-    Bip84 = 84,
+/// Derivation scheme by bip purpose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeriveMethod {
+    /// m/44'/coin'/account'/chain/index [p2pkh]
+    Bip44,
+    /// m/49'/coin'/account'/chain/index [p2shwpkh]
+    Bip49,
+    /// m/84'/coin'/account'/chain/index [p2wpkh]
+    Bip84,
+    /// m/86'/coin'/account'/chain/index [p2tr]
+    Bip86,
+    /// m/48'/coin'/account'/1'/chain/index [p2shwsh multisig]
+    Bip48Nested,
+    /// m/48'/coin'/account'/2'/chain/index [p2wsh multisig]
+    Bip48,
 }
 
 type DeriveResult = anyhow::Result<(String, String)>;
 
 use DeriveMethod::*;
 
+static SECP: LazyLock<Secp256k1<All>> = LazyLock::new(Secp256k1::new);
+
 impl DerivePath {
     #[inline]
     fn method(&self) -> DeriveMethod {
         match self {
             Self { bip44: true, .. } => Bip44,
             Self { bip84: true, .. } => Bip84,
+            Self { bip48: true, .. } => Bip48,
+            Self {
+                bip48_nested: true, ..
+            } => Bip48Nested,
             _ => Bip49,
         }
     }
 
     #[inline]
     pub fn path(&self, account: u32) -> String {
-        match artimonist::NETWORK.is_mainnet() {
-            true => format!("m/{}'/0'/{account}'", self.method() as u8),
-            false => format!("m/{}'/1'/{account}'", self.method() as u8),
-        }
+        self.method().path(account)
     }
 
+    /// Derive the account key once, wallets derive from it by `/chain/index`.
     #[inline]
-    pub fn account(&self, root: &Xpriv, account: u32) -> DeriveResult {
-        Ok(match self.method() {
-            Bip44 => root.bip44_account(account)?,
-            Bip49 => root.bip49_account(account)?,
-            Bip84 => root.bip84_account(account)?,
-        })
+    pub fn account_key(&self, root: &Xpriv, account: u32) -> anyhow::Result<Xpriv> {
+        self.method().account_key(root, account)
     }
 
+    /// Account (xpub, xpriv)
     #[inline]
-    pub fn wallet(&self, root: &Xpriv, account: u32, index: u32) -> DeriveResult {
-        Ok(match self.method() {
-            Bip44 => root.bip44_wallet(account, index, false)?,
-            Bip49 => root.bip49_wallet(account, index, false)?,
-            Bip84 => root.bip84_wallet(account, index, false)?,
-        })
+    pub fn account(&self, account: &Xpriv) -> (String, String) {
+        let xpub = Xpub::from_priv(&SECP, account);
+        (xpub.to_string(), account.to_string())
+    }
+
+    /// Wallet (address, wif) of the account at `0/index`
+    #[inline]
+    pub fn wallet(&self, account: &Xpriv, index: u32) -> DeriveResult {
+        self.method().wallet(account, false, index)
     }
 
+    /// Multisig wallet (address, script) of M-of-N accounts at `0/index`
     #[inline]
-    pub fn multisig<const M: u8, const N: u8>(
+    pub fn multisig(
         &self,
-        root: &Xpriv,
-        account: u32,
+        script: Option<MultisigScript>,
+        sorted: bool,
+        m: u8,
+        accounts: &[Xpriv],
         index: u32,
     ) -> DeriveResult {
-        Ok(match self.method() {
-            Bip44 => root.bip44_multisig::<M, N>(account, index)?,
-            Bip49 => root.bip49_multisig::<M, N>(account, index)?,
-            Bip84 => root.bip84_multisig::<M, N>(account, index)?,
+        self.multisig_script(script)
+            .wallet(m, accounts, sorted, index)
+    }
+
+    /// Multisig script type, by the derive path if not specified
+    #[inline]
+    pub fn multisig_script(&self, script: Option<MultisigScript>) -> MultisigScript {
+        script.unwrap_or(self.method().multisig_script())
+    }
+}
+
+impl DeriveMethod {
+    /// Single signature schemes
+    pub const SINGLE: [DeriveMethod; 4] = [Bip44, Bip49, Bip84, Bip86];
+    /// Multiple signatures schemes
+    pub const MULTISIG: [DeriveMethod; 5] = [Bip44, Bip49, Bip84, Bip48Nested, Bip48];
+
+    #[inline]
+    pub fn path(self, account: u32) -> String {
+        let coin = if artimonist::NETWORK.is_mainnet() {
+            0
+        } else {
+            1
+        };
+        match self {
+            Bip44 => format!("m/44'/{coin}'/{account}'"),
+            Bip49 => format!("m/49'/{coin}'/{account}'"),
+            Bip84 => format!("m/84'/{coin}'/{account}'"),
+            Bip86 => format!("m/86'/{coin}'/{account}'"),
+            Bip48Nested => format!("m/48'/{coin}'/{account}'/1'"),
+            Bip48 => format!("m/48'/{coin}'/{account}'/2'"),
+        }
+    }
+
+    #[inline]
+    pub fn account_key(self, root: &Xpriv, account: u32) -> anyhow::Result<Xpriv> {
+        let path = DerivationPath::from_str(&self.path(account))?;
+        Ok(root.derive_priv(&SECP, &path)?)
+    }
+
+    /// Address of the account at `chain/index`
+    #[inline]
+    pub fn address(self, account: &Xpriv, change: bool, index: u32) -> anyhow::Result<Address> {
+        let xpriv = account.derive_priv(&SECP, &child_path(change, index)?)?;
+        self.to_address(&PublicKey::from_private_key(&SECP, &xpriv.to_priv()))
+    }
+
+    /// Wallet (address, wif) of the account at `chain/index`
+    #[inline]
+    pub fn wallet(self, account: &Xpriv, change: bool, index: u32) -> DeriveResult {
+        let xpriv = account.derive_priv(&SECP, &child_path(change, index)?)?;
+        let pub_key = PublicKey::from_private_key(&SECP, &xpriv.to_priv());
+        let address = self.to_address(&pub_key)?;
+        Ok((address.to_string(), xpriv.to_priv().to_wif()))
+    }
+
+    fn to_address(self, pub_key: &PublicKey) -> anyhow::Result<Address> {
+        Ok(match self {
+            Bip44 => Address::p2pkh(pub_key, artimonist::NETWORK),
+            Bip49 => Address::p2shwpkh(&(*pub_key).try_into()?, artimonist::NETWORK),
+            Bip84 => Address::p2wpkh(&(*pub_key).try_into()?, network()),
+            Bip86 => Address::p2tr(&SECP, pub_key.inner.into(), None, network()),
+            Bip48Nested | Bip48 => return Err(anyhow!("BIP48 derivation is only for multisig")),
         })
     }
+
+    /// Multisig script type of the derive path
+    #[inline]
+    pub fn multisig_script(self) -> MultisigScript {
+        match self {
+            Bip48Nested => MultisigScript::P2shP2wsh,
+            Bip48 => MultisigScript::P2wsh,
+            _ => MultisigScript::P2sh,
+        }
+    }
+}
+
+/// BIP341 unspendable internal key "H" of taproot script path
+pub const NUMS_KEY: [u8; 32] = [
+    0x50, 0x92, 0x9b, 0x74, 0xc1, 0xa0, 0x49, 0x54, 0xb7, 0x8b, 0x4b, 0x60, 0x35, 0xe9, 0x7a, 0x5e,
+    0x07, 0x8a, 0x5a, 0x0f, 0x28, 0xec, 0x96, 0xd5, 0x47, 0xbf, 0xee, 0x9a, 0xce, 0x80, 0x3a, 0xc0,
+];
+
+impl MultisigScript {
+    /// Multisig wallet (address, script) of M-of-N accounts at `0/index`,
+    ///   keys sorted as descriptor `sortedmulti`, or in account order as `multi`.
+    pub fn wallet(self, m: u8, accounts: &[Xpriv], sorted: bool, index: u32) -> DeriveResult {
+        let path = child_path(false, index)?;
+        let mut pub_keys = accounts
+            .iter()
+            .map(|account| {
+                let xpriv = account.derive_priv(&SECP, &path)?;
+                Ok(PublicKey::from_private_key(&SECP, &xpriv.to_priv()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        match self {
+            // sortedmulti_a of BIP387 sorts the x-only keys
+            MultisigScript::P2tr if sorted => {
+                pub_keys.sort_by_key(|key| key.inner.x_only_public_key().0.serialize())
+            }
+            _ if sorted => pub_keys.sort(),
+            _ => {}
+        }
+
+        let script = match self {
+            // <k1> OP_CHECKSIG <k2> OP_CHECKSIGADD ... <m> OP_NUMEQUAL
+            MultisigScript::P2tr => pub_keys
+                .iter()
+                .enumerate()
+                .fold(Builder::new(), |builder, (i, key)| {
+                    let builder = builder.push_x_only_key(&key.inner.into());
+                    match i {
+                        0 => builder.push_opcode(opcodes::all::OP_CHECKSIG),
+                        _ => builder.push_opcode(opcodes::all::OP_CHECKSIGADD),
+                    }
+                })
+                .push_int(m as i64)
+                .push_opcode(opcodes::all::OP_NUMEQUAL)
+                .into_script(),
+            _ => pub_keys
+                .iter()
+                .fold(Builder::new().push_int(m as i64), |builder, key| {
+                    builder.push_key(key)
+                })
+                .push_int(pub_keys.len() as i64)
+                .push_opcode(opcodes::all::OP_CHECKMULTISIG)
+                .into_script(),
+        };
+        let address = match self {
+            MultisigScript::P2sh => Address::p2sh(&script, artimonist::NETWORK)?,
+            MultisigScript::P2shP2wsh => Address::p2shwsh(&script, artimonist::NETWORK),
+            MultisigScript::P2wsh => Address::p2wsh(&script, network()),
+            MultisigScript::P2tr => {
+                let internal_key = XOnlyPublicKey::from_slice(&NUMS_KEY)?;
+                let spend_info = TaprootBuilder::new()
+                    .add_leaf(0, script.clone())?
+                    .finalize(&SECP, internal_key)
+                    .map_err(|_| anyhow!("Taproot tree is incomplete"))?;
+                Address::p2tr_tweaked(spend_info.output_key(), network())
+            }
+        };
+        Ok((address.to_string(), script.to_hex_string()))
+    }
+}
+
+/// Key origin notation: `[fingerprint/path]`
+#[inline]
+pub fn key_origin(fingerprint: Fingerprint, path: &str) -> String {
+    let path = path.trim_start_matches('m').trim_end_matches('/');
+    format!("[{fingerprint}{path}]")
+}
+
+/// Label of the root key fingerprint, only a master key (depth 0) is the origin of derived keys
+#[inline]
+pub fn fingerprint_label(depth: u8) -> &'static str {
+    match depth {
+        0 => "Master fingerprint",
+        _ => "Root fingerprint",
+    }
+}
+
+#[inline(always)]
+fn child_path(change: bool, index: u32) -> anyhow::Result<[ChildNumber; 2]> {
+    Ok([
+        ChildNumber::from_normal_idx(change as u32)?,
+        ChildNumber::from_normal_idx(index)?,
+    ])
+}
+
+/// Network of segwit addresses
+#[inline(always)]
+pub fn network() -> Network {
+    match artimonist::NETWORK.is_mainnet() {
+        true => Network::Bitcoin,
+        false => Network::Testnet,
+    }
 }
@@ -1,7 +1,10 @@
+use crate::utils::default_jobs;
 use artimonist::{GenericDiagram, Language};
 use clap::builder::TypedValueParser;
+use std::path::Path;
 
 #[derive(clap::Parser, Debug)]
+#[command(group(clap::ArgGroup::new("card_file").args(["svg", "pdf"]).multiple(true)))]
 pub struct DiagramCommand<T: GenericDiagram> {
     /// Diagram type
     #[clap(skip)]
@@ -15,14 +18,38 @@ pub struct DiagramCommand<T: GenericDiagram> {
     #[clap(short = 'm', long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(0..65536))]
     pub amount: u32,
 
-    /// Input diagram from text file
+    /// Amount of worker threads [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Input diagram from text, json or toml file ('-' for stdin)
     #[clap(short, long)]
     pub file: Option<String>,
 
+    /// Input diagram from stdin
+    #[clap(long, conflicts_with = "file")]
+    pub stdin: bool,
+
     /// Export unicode view for non-displayable character
     #[clap(long)]
     pub unicode: bool,
 
+    /// Export printable diagram card to svg file
+    #[clap(long, value_name = "FILE")]
+    pub svg: Option<String>,
+
+    /// Export printable diagram card to pdf file
+    #[clap(long, value_name = "FILE")]
+    pub pdf: Option<String>,
+
+    /// Diagram card style
+    #[clap(long, value_enum, default_value_t = CardStyle::Full)]
+    pub card: CardStyle,
+
+    /// Only export the diagram card, without printing diagram or secrets
+    #[clap(long, requires = "card_file")]
+    pub card_only: bool,
+
     /// Generation target
     #[command(flatten)]
     pub target: GenerateTarget,
@@ -62,7 +89,42 @@ pub struct GenerateTarget {
     pub passphrase: bool,
 }
 
+/// Diagram card style
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardStyle {
+    /// Show the content of filled cells
+    Full,
+    /// Show only which cells are filled
+    Masked,
+    /// Show empty cells
+    Blank,
+}
+
 impl<T: GenericDiagram> DiagramCommand<T> {
+    /// Read diagram from stdin by `--stdin` or `--file -`.
+    #[inline]
+    pub fn is_stdin(&self) -> bool {
+        self.stdin || self.file.as_deref() == Some("-")
+    }
+
+    #[inline]
+    pub fn file_extension(&self) -> Option<&str> {
+        match self.is_stdin() {
+            true => None,
+            false => Path::new(self.file.as_ref()?).extension()?.to_str(),
+        }
+    }
+
+    #[inline]
+    pub fn indices(&self) -> Vec<u32> {
+        (self.index..self.index + self.amount).collect()
+    }
+
+    #[inline]
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(default_jobs)
+    }
+
     #[inline(always)]
     pub fn has_mnemonic(&self) -> bool {
         self.target.mnemonic.is_some()
@@ -0,0 +1,281 @@
+use super::arg::CardStyle;
+use super::file::{DiagramKind, Engine};
+use anyhow::anyhow;
+use std::fmt::Write;
+
+const CELL: usize = 64;
+const MARGIN: usize = 32;
+const HEADER: usize = 48;
+const FOOTER: usize = 56;
+const WIDTH: usize = MARGIN * 2 + CELL * 7;
+const HEIGHT: usize = MARGIN * 2 + HEADER + CELL * 7 + FOOTER;
+const CELL_PADDING: usize = 4;
+const MAX_FONT_SIZE: f64 = 28.0;
+const MIN_FONT_SIZE: f64 = 10.0;
+const MAX_LINES: usize = 10;
+/// Line height relative to font size
+const LINE_HEIGHT: f64 = 1.2;
+
+/// Printable backup card of a diagram
+pub struct Card {
+    pub cells: Vec<Vec<Option<String>>>,
+    pub style: CardStyle,
+    pub kind: DiagramKind,
+    pub engine: Engine,
+    pub fingerprint: String,
+}
+
+impl Card {
+    fn title(&self) -> String {
+        format!("Artimonist {:?} Diagram", self.kind)
+    }
+
+    fn footer(&self) -> String {
+        let engine = match self.engine {
+            Engine::V1 => "v1",
+            Engine::V2 => "v2",
+        };
+        format!("Engine: {engine}    Fingerprint: {}", self.fingerprint)
+    }
+
+    /// Font size and lines of the pieces fitting the cell,
+    ///   text is wrapped into the fewest lines of a readable font size.
+    fn fit_cell(pieces: &[String]) -> (f64, Vec<String>) {
+        let room = (CELL - CELL_PADDING * 2) as f64;
+        let total = pieces.iter().map(|p| text_width(p)).sum::<f64>();
+        let mut best = (0.0, vec![]);
+        for n in 1..=pieces.len().clamp(1, MAX_LINES) {
+            let lines = wrap(pieces, total / n as f64);
+            let widest = lines.iter().map(|s| text_width(s)).fold(0.0, f64::max);
+            let size = (room / widest.max(1.0))
+                .min(room / (lines.len() as f64 * LINE_HEIGHT))
+                .min(MAX_FONT_SIZE);
+            if size >= MIN_FONT_SIZE {
+                return (size, lines);
+            }
+            if size > best.0 {
+                best = (size, lines);
+            }
+        }
+        best
+    }
+
+    /// Vertical centers of the lines around the cell center
+    fn line_centers(cy: usize, size: f64, lines: usize) -> impl Iterator<Item = f64> {
+        let first = cy as f64 - (lines as f64 - 1.0) * size * LINE_HEIGHT / 2.0;
+        (0..lines).map(move |i| first + i as f64 * size * LINE_HEIGHT)
+    }
+
+    /// Cell origin from the top left corner of the card
+    fn origin(row: usize, col: usize) -> (usize, usize) {
+        (MARGIN + col * CELL, MARGIN + HEADER + row * CELL)
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize, &str)> {
+        self.cells.iter().enumerate().flat_map(|(row, values)| {
+            values.iter().enumerate().filter_map(move |(col, v)| {
+                v.as_deref()
+                    .filter(|s| !s.is_empty())
+                    .map(|s| (row, col, s))
+            })
+        })
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif">"#
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="20" text-anchor="middle">{}</text>"#,
+            WIDTH / 2,
+            MARGIN + HEADER / 2,
+            xml_escape(&self.title())
+        );
+
+        // grid
+        let _ = writeln!(svg, r#"<g fill="none" stroke="black">"#);
+        for (row, col) in (0..7).flat_map(|r| (0..7).map(move |c| (r, c))) {
+            let (x, y) = Self::origin(row, col);
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x}" y="{y}" width="{CELL}" height="{CELL}"/>"#
+            );
+        }
+        let _ = writeln!(svg, "</g>");
+
+        // cells
+        for (row, col, value) in self.cells() {
+            let (x, y) = Self::origin(row, col);
+            match self.style {
+                CardStyle::Full => {
+                    let pieces = value.chars().map(String::from).collect::<Vec<_>>();
+                    let (size, lines) = Self::fit_cell(&pieces);
+                    let centers = Self::line_centers(y + CELL / 2, size, lines.len());
+                    for (line, cy) in lines.iter().zip(centers) {
+                        let _ = writeln!(
+                            svg,
+                            r#"<text x="{}" y="{cy:.1}" font-size="{size:.1}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                            x + CELL / 2,
+                            xml_escape(line)
+                        );
+                    }
+                }
+                CardStyle::Masked => {
+                    let _ = writeln!(
+                        svg,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#888"/>"##,
+                        x + 8,
+                        y + 8,
+                        CELL - 16,
+                        CELL - 16
+                    );
+                }
+                CardStyle::Blank => {}
+            }
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="14" text-anchor="middle">{}</text>"#,
+            WIDTH / 2,
+            HEIGHT - MARGIN - FOOTER / 2,
+            xml_escape(&self.footer())
+        );
+        let _ = writeln!(svg, "</svg>");
+        svg
+    }
+
+    /// Single page pdf with standard Helvetica font,
+    ///   filled cells of a full card must be ascii text.
+    pub fn to_pdf(&self) -> anyhow::Result<Vec<u8>> {
+        if self.style == CardStyle::Full && !self.cells().all(|(_, _, s)| s.is_ascii()) {
+            return Err(anyhow!(
+                "Pdf card does not support non-ascii cells, export it by --svg instead"
+            ));
+        }
+
+        // pdf coordinates start from the bottom left corner
+        let text = |s: &str, size: f64, cx: usize, cy: f64| {
+            let width = text_width(s) * size;
+            format!(
+                "BT /F1 {size:.1} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+                (cx as f64 - width / 2.0).max(0.0),
+                HEIGHT as f64 - cy - size / 3.0,
+                pdf_escape(s)
+            )
+        };
+
+        let mut content = String::new();
+        let title = (MARGIN + HEADER / 2) as f64;
+        content += &text(&self.title(), 20.0, WIDTH / 2, title);
+        for (row, col) in (0..7).flat_map(|r| (0..7).map(move |c| (r, c))) {
+            let (x, y) = Self::origin(row, col);
+            content += &format!("{x} {} {CELL} {CELL} re S\n", HEIGHT - y - CELL);
+        }
+        for (row, col, value) in self.cells() {
+            let (x, y) = Self::origin(row, col);
+            match self.style {
+                CardStyle::Full => {
+                    let pieces = value.chars().map(String::from).collect::<Vec<_>>();
+                    let (size, lines) = Self::fit_cell(&pieces);
+                    let centers = Self::line_centers(y + CELL / 2, size, lines.len());
+                    for (line, cy) in lines.iter().zip(centers) {
+                        content += &text(line, size, x + CELL / 2, cy);
+                    }
+                }
+                CardStyle::Masked => {
+                    let (w, bottom) = (CELL - 16, HEIGHT - y - CELL + 8);
+                    content += &format!("0.55 g {} {bottom} {w} {w} re f 0 g\n", x + 8);
+                }
+                CardStyle::Blank => {}
+            }
+        }
+        let footer = (HEIGHT - MARGIN - FOOTER / 2) as f64;
+        content += &text(&self.footer(), 14.0, WIDTH / 2, footer);
+
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {WIDTH} {HEIGHT}] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>"
+            ),
+            format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        for (i, obj) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{obj}\nendobj\n", i + 1);
+        }
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        offsets.iter().for_each(|n| {
+            let _ = writeln!(pdf, "{n:010} 00000 n ");
+        });
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%EOF\n",
+            objects.len() + 1
+        );
+        Ok(pdf.into_bytes())
+    }
+}
+
+/// Estimated text width in em, wide chars take a full em
+fn text_width(s: &str) -> f64 {
+    s.chars()
+        .map(|c| match c.is_ascii() {
+            true => 0.55,
+            false => 1.0,
+        })
+        .sum()
+}
+
+/// Join the pieces into lines of about the target width
+fn wrap(pieces: &[String], target: f64) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut width = 0.0;
+    for piece in pieces {
+        let w = text_width(piece);
+        match lines.last_mut() {
+            Some(line) if width + w <= target + f64::EPSILON => line.push_str(piece),
+            _ => {
+                lines.push(piece.clone());
+                width = 0.0;
+            }
+        }
+        width += w;
+    }
+    lines
+}
+
+fn xml_escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn pdf_escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            _ => c.to_string(),
+        })
+        .collect()
+}
@@ -1,24 +1,43 @@
 use super::DiagramCommand;
-use crate::utils::{inquire_password, select_language, unicode_decode, unicode_encode};
+use super::card::Card;
+use super::file::{DiagramFile, DiagramKind, Engine};
+use crate::encrypt::encrypt_key;
+use crate::utils::{
+    inquire_password, ordered_for_each, select_language, unicode_decode, unicode_encode,
+};
 use anyhow::anyhow;
-use artimonist::{BIP38, BIP85, Diagram, GenericDiagram, Language, Xpriv};
+use artimonist::{BIP85, Diagram, GenericDiagram, Language, Xpriv};
 use std::any::type_name;
 use std::io::{BufWriter, Write};
+use std::str::FromStr;
 
 type Result<T> = anyhow::Result<T>;
 
-const WORD_MAX_LENGTH: usize = 20;
+pub const WORD_MAX_LENGTH: usize = 20;
 
 impl<T: GenericDiagram> crate::Execute for DiagramCommand<T> {
     fn execute(&mut self) -> Result<()> {
-        // load matrix data from file or inquire it from user
-        let items = match &self.file {
-            Some(file) => from_art_file(file)?,
+        // load matrix data from file, stdin or inquire it from user
+        let content = match &self.file {
+            _ if self.is_stdin() => Some(std::io::read_to_string(std::io::stdin())?),
+            Some(file) => Some(std::fs::read_to_string(file)?),
+            None => None,
+        };
+        let mut fingerprints = vec![];
+        let items = match content {
+            Some(content) => match DiagramFile::parse(&content, self.file_extension())? {
+                Some(diagram_file) => {
+                    self.apply_file(&diagram_file)?;
+                    fingerprints = diagram_file.fingerprints.clone();
+                    diagram_file.items(WORD_MAX_LENGTH)?
+                }
+                None => from_art_text(&content),
+            },
             None => from_inquire()?,
         };
 
         // choose a mnemonic language if needed
-        if self.has_mnemonic() && self.language.is_none() {
+        if self.has_mnemonic() && !self.card_only && self.language.is_none() {
             self.language = Some(select_language(Language::all())?);
         }
 
@@ -38,6 +57,7 @@ impl<T: GenericDiagram> crate::Execute for DiagramCommand<T> {
                 true => diagram.to_master_v1(password.as_bytes())?,
                 false => diagram.to_master(password.as_bytes())?,
             };
+            verify_fingerprint(&master, &fingerprints)?;
             self.display(diagram.0, &master)?;
         } else if type_name::<T>().contains("ComplexDiagram") {
             let diagram = items.art_complex_diagram()?;
@@ -45,6 +65,7 @@ impl<T: GenericDiagram> crate::Execute for DiagramCommand<T> {
                 true => diagram.to_master_v1(password.as_bytes())?,
                 false => diagram.to_master(password.as_bytes())?,
             };
+            verify_fingerprint(&master, &fingerprints)?;
             self.display(diagram.0, &master)?;
         } else {
             return Err(anyhow::anyhow!("Unsupported diagram type"));
@@ -53,17 +74,56 @@ impl<T: GenericDiagram> crate::Execute for DiagramCommand<T> {
     }
 }
 
-fn from_art_file(path: &str) -> Result<Vec<String>> {
-    use std::io::{BufRead, BufReader};
-    let mvs = BufReader::new(std::fs::File::open(path)?)
-        .lines()
-        .take(7)
-        .flat_map(|line| match line {
-            Ok(ln) => parse_7_values(&ln),
-            _ => vec![String::new(); 7],
-        })
-        .collect::<Vec<_>>();
-    Ok(mvs)
+impl<T: GenericDiagram> DiagramCommand<T> {
+    /// Check diagram type and engine version of the structured diagram file.
+    fn apply_file(&mut self, file: &DiagramFile) -> Result<()> {
+        let kind = diagram_kind::<T>()?;
+        if file.kind != kind {
+            return Err(anyhow!(
+                "Diagram file is {:?} type, not for {:?} command",
+                file.kind,
+                kind
+            ));
+        }
+        match file.engine {
+            Engine::V1 => self.version_v1 = true,
+            Engine::V2 if self.version_v1 => {
+                return Err(anyhow!(
+                    "Diagram file requires engine v2, conflicts with --v1"
+                ));
+            }
+            Engine::V2 => {}
+        }
+        Ok(())
+    }
+}
+
+fn diagram_kind<T: GenericDiagram>() -> Result<DiagramKind> {
+    match type_name::<T>() {
+        s if s.contains("SimpleDiagram") => Ok(DiagramKind::Simple),
+        s if s.contains("ComplexDiagram") => Ok(DiagramKind::Complex),
+        _ => Err(anyhow!("Unsupported diagram type")),
+    }
+}
+
+/// Check the master fingerprint against the expected fingerprints of diagram file
+pub fn verify_fingerprint(master: &Xpriv, fingerprints: &[String]) -> Result<()> {
+    let secp = artimonist::bitcoin::secp256k1::Secp256k1::signing_only();
+    let fingerprint = master.fingerprint(&secp).to_string();
+    if !fingerprints.is_empty()
+        && !fingerprints
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(&fingerprint))
+    {
+        return Err(anyhow!(
+            "Master fingerprint {fingerprint} does not match the diagram file"
+        ));
+    }
+    Ok(())
+}
+
+fn from_art_text(content: &str) -> Vec<String> {
+    content.lines().take(7).flat_map(parse_7_values).collect()
 }
 
 fn from_inquire() -> Result<Vec<String>> {
@@ -95,7 +155,7 @@ fn parse_7_values(line: &str) -> Vec<String> {
         .collect::<Vec<_>>()
 }
 
-trait ComfyTable<T> {
+pub trait ComfyTable<T> {
     fn fmt_table(&self, unicode: bool) -> comfy_table::Table;
 }
 
@@ -130,6 +190,7 @@ where
 
 trait DisplayTargets {
     fn display<T: ToString>(&self, mx: [[Option<T>; 7]; 7], master: &Xpriv) -> Result<()>;
+    fn export_card<T: ToString>(&self, mx: &[[Option<T>; 7]; 7], master: &Xpriv) -> Result<()>;
     fn derive_all(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()>;
     fn mnemonic(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()>;
     fn wif(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()>;
@@ -139,6 +200,10 @@ trait DisplayTargets {
 
 impl<D: GenericDiagram> DisplayTargets for DiagramCommand<D> {
     fn display<T: ToString>(&self, mx: [[Option<T>; 7]; 7], master: &Xpriv) -> Result<()> {
+        // the card alone keeps the diagram and secrets off the terminal
+        if self.card_only {
+            return self.export_card(&mx, master);
+        }
         let f = &mut BufWriter::new(std::io::stdout());
 
         // diagram view
@@ -153,11 +218,46 @@ impl<D: GenericDiagram> DisplayTargets for DiagramCommand<D> {
             writeln!(f, "{}", mx.fmt_table(true))?;
         }
 
+        // printable card
+        if self.svg.is_some() || self.pdf.is_some() {
+            self.export_card(&mx, master)?;
+        }
+
         // generation results
         self.derive_all(master, f)?;
         Ok(())
     }
 
+    fn export_card<T: ToString>(&self, mx: &[[Option<T>; 7]; 7], master: &Xpriv) -> Result<()> {
+        let secp = artimonist::bitcoin::secp256k1::Secp256k1::signing_only();
+        let card = Card {
+            cells: mx
+                .iter()
+                .map(|r| {
+                    r.iter()
+                        .map(|v| v.as_ref().map(|x| x.to_string()))
+                        .collect()
+                })
+                .collect(),
+            style: self.card,
+            kind: diagram_kind::<D>()?,
+            engine: if self.version_v1 {
+                Engine::V1
+            } else {
+                Engine::V2
+            },
+            fingerprint: master.fingerprint(&secp).to_string(),
+        };
+        let pdf = self.pdf.as_ref().map(|_| card.to_pdf()).transpose()?;
+        if let Some(path) = &self.svg {
+            std::fs::write(path, card.to_svg())?;
+        }
+        if let (Some(path), Some(pdf)) = (&self.pdf, pdf) {
+            std::fs::write(path, pdf)?;
+        }
+        Ok(())
+    }
+
     #[inline]
     fn derive_all(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()> {
         if self.has_mnemonic() {
@@ -183,42 +283,54 @@ impl<D: GenericDiagram> DisplayTargets for DiagramCommand<D> {
     fn mnemonic(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()> {
         writeln!(f, "Mnemonics: ")?;
         let length = self.target.mnemonic.unwrap_or(24) as u32;
-        for index in self.index..self.index + self.amount {
-            let language = self.language.ok_or(anyhow::anyhow!("unkown language"))?;
-            let mnemonic = master.bip85_mnemonic(index, length, language)?;
-            writeln!(f, "({index}): {mnemonic}")?;
-        }
-        Ok(())
+        let language = self.language.ok_or(anyhow::anyhow!("unkown language"))?;
+        ordered_for_each(
+            &self.indices(),
+            self.jobs(),
+            |&index| Ok(master.bip85_mnemonic(index, length, language)?),
+            |index, mnemonic| Ok(writeln!(f, "({index}): {mnemonic}")?),
+        )
     }
 
     #[inline]
     fn wif(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()> {
         let password = self.password.as_ref().ok_or(anyhow!("empty password"))?;
         writeln!(f, "Wifs: ")?;
-        for index in self.index..self.index + self.amount {
-            let artimonist::Wif { addr, pk } = master.bip85_wallet(index)?;
-            writeln!(f, "({index}): {addr}, {}", pk.bip38_encrypt(password)?)?;
-        }
-        Ok(())
+        ordered_for_each(
+            &self.indices(),
+            self.jobs(),
+            |&index| {
+                let artimonist::Wif { addr, pk } = master.bip85_wallet(index)?;
+                Ok((addr, encrypt_key(&pk, password, false)?))
+            },
+            |index, (addr, pk)| Ok(writeln!(f, "({index}): {addr}, {pk}")?),
+        )
     }
 
     #[inline]
     fn xpriv(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()> {
+        let secp = artimonist::bitcoin::secp256k1::Secp256k1::signing_only();
         writeln!(f, "Xprvs: ")?;
-        for index in self.index..self.index + self.amount {
-            let xpriv = master.bip85_master(index)?;
-            writeln!(f, "({index}): {xpriv}")?;
-        }
-        Ok(())
+        writeln!(f, "Master fingerprint: {}", master.fingerprint(&secp))?;
+        ordered_for_each(
+            &self.indices(),
+            self.jobs(),
+            |&index| {
+                let xpriv = Xpriv::from_str(&master.bip85_master(index)?)?;
+                Ok((xpriv.fingerprint(&secp), xpriv))
+            },
+            |index, (fingerprint, xpriv)| Ok(writeln!(f, "({index}): {fingerprint}, {xpriv}")?),
+        )
     }
 
     #[inline]
     fn pwd(&self, master: &Xpriv, f: &mut impl Write) -> anyhow::Result<()> {
         writeln!(f, "Passwords: ")?;
-        for index in self.index..self.index + self.amount {
-            let pwd = master.bip85_password(index, 20, Default::default())?;
-            writeln!(f, "({index}): {pwd}")?;
-        }
-        Ok(())
+        ordered_for_each(
+            &self.indices(),
+            self.jobs(),
+            |&index| Ok(master.bip85_password(index, 20, Default::default())?),
+            |index, pwd| Ok(writeln!(f, "({index}): {pwd}")?),
+        )
     }
 }
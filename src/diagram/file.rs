@@ -0,0 +1,163 @@
+use crate::utils::unicode_decode;
+use anyhow::anyhow;
+use artimonist::{Diagram, GenericDiagram, Xpriv};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+type Result<T> = anyhow::Result<T>;
+
+/// Structured diagram file in json or toml format.
+///
+/// # Examples
+/// ```toml
+/// type = "simple"
+/// engine = "v1"
+/// fingerprints = ["1a2b3c4d"]
+/// cells = [
+///   { row = 1, col = 7, value = "0" },
+/// ]
+/// ```
+/// `cells` can also be a full 7 * 7 array of strings, rows and columns start from 1.
+/// Forgotten cells for recover command are marked by `unknown = true` or `candidates = [..]`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DiagramFile {
+    /// Diagram type
+    #[serde(rename = "type")]
+    pub kind: DiagramKind,
+
+    /// Generate engine version
+    #[serde(default)]
+    pub engine: Engine,
+
+    /// Diagram cells
+    pub cells: Cells,
+
+    /// Expected master key fingerprints
+    #[serde(default)]
+    pub fingerprints: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramKind {
+    Simple,
+    Complex,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    V1,
+    #[default]
+    V2,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum Cells {
+    /// Explicit 7 * 7 array
+    Matrix(Vec<Vec<Option<String>>>),
+    /// Sparse list of filled cells
+    Sparse(Vec<Cell>),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Cell {
+    pub row: usize,
+    pub col: usize,
+    #[serde(default)]
+    pub value: String,
+    /// Candidate values of a forgotten cell
+    #[serde(default)]
+    pub candidates: Vec<String>,
+    /// Forgotten cell, search it in the recover charset
+    #[serde(default)]
+    pub unknown: bool,
+}
+
+impl DiagramFile {
+    /// Parse structured diagram by file extension or content, `None` for text diagram.
+    pub fn parse(content: &str, extension: Option<&str>) -> Result<Option<Self>> {
+        let is_toml =
+            || toml::from_str::<toml::Table>(content).is_ok_and(|t| t.contains_key("type"));
+        let file = match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("json") => serde_json::from_str(content)?,
+            Some("toml") => toml::from_str(content)?,
+            Some(_) => return Ok(None),
+            None if content.trim_start().starts_with('{') => serde_json::from_str(content)?,
+            None if is_toml() => toml::from_str(content)?,
+            None => return Ok(None),
+        };
+        Ok(Some(file))
+    }
+
+    /// Diagram items in row-major order
+    pub fn items(&self, max_length: usize) -> Result<Vec<String>> {
+        let candidates = self.candidates(max_length, &[])?;
+        if candidates.iter().any(|vs| vs.len() != 1) {
+            return Err(anyhow!(
+                "Diagram file has unknown cells, use recover command to search them"
+            ));
+        }
+        Ok(candidates.into_iter().flatten().collect())
+    }
+
+    /// Master key of the diagram items by the file type and engine
+    pub fn to_master(&self, items: &[String], salt: &[u8]) -> Result<Xpriv> {
+        Ok(match (self.kind, self.engine) {
+            (DiagramKind::Simple, Engine::V1) => items.art_simple_diagram()?.to_master_v1(salt)?,
+            (DiagramKind::Simple, Engine::V2) => items.art_simple_diagram()?.to_master(salt)?,
+            (DiagramKind::Complex, Engine::V1) => {
+                items.art_complex_diagram()?.to_master_v1(salt)?
+            }
+            (DiagramKind::Complex, Engine::V2) => items.art_complex_diagram()?.to_master(salt)?,
+        })
+    }
+
+    /// Candidate values of each cell in row-major order,
+    ///   unknown cells take values from the charset.
+    pub fn candidates(&self, max_length: usize, charset: &[String]) -> Result<Vec<Vec<String>>> {
+        let mut items = vec![vec![String::new()]; 7 * 7];
+        match &self.cells {
+            Cells::Matrix(rows) => {
+                if rows.len() > 7 || rows.iter().any(|r| r.len() > 7) {
+                    return Err(anyhow!("Diagram cells exceed 7 * 7"));
+                }
+                for (row, values) in rows.iter().enumerate() {
+                    for (col, value) in values.iter().enumerate() {
+                        if let Some(v) = value {
+                            items[row * 7 + col] = vec![v.clone()];
+                        }
+                    }
+                }
+            }
+            Cells::Sparse(cells) => {
+                let mut filled = HashSet::new();
+                for cell in cells {
+                    let (row, col) = (cell.row, cell.col);
+                    if !(1..=7).contains(&row) || !(1..=7).contains(&col) {
+                        return Err(anyhow!("Invalid diagram cell: ({row}, {col})"));
+                    }
+                    if !filled.insert((row, col)) {
+                        return Err(anyhow!("Duplicate diagram cell: ({row}, {col})"));
+                    }
+                    items[(row - 1) * 7 + (col - 1)] = match cell {
+                        Cell { unknown: true, .. } => charset.to_vec(),
+                        Cell { candidates, .. } if !candidates.is_empty() => candidates.clone(),
+                        Cell { value, .. } => vec![value.clone()],
+                    };
+                }
+            }
+        }
+        Ok(items
+            .iter()
+            .map(|vs| {
+                vs.iter()
+                    .map(|s| unicode_decode(s).chars().take(max_length).collect())
+                    .collect()
+            })
+            .collect())
+    }
+}
@@ -1,4 +1,8 @@
 mod arg;
+mod card;
 mod execute;
+mod file;
 
 pub use arg::DiagramCommand;
+pub use execute::{ComfyTable, WORD_MAX_LENGTH, verify_fingerprint};
+pub use file::{DiagramFile, DiagramKind};
@@ -1,16 +1,106 @@
+use super::ec::{is_confirmation_code, is_intermediate_code};
+use crate::utils::default_jobs;
 use artimonist::bitcoin;
 
 #[derive(clap::Parser)]
+#[command(group(clap::ArgGroup::new("candidates").args(["wordlist", "guess"])))]
 pub struct EncryptCommand<const ENCRYPT: bool> {
-    /// Mnemonic or private key
-    #[clap(name = "MNEMONIC|PRIVATE KEY|FILE NAME")]
-    pub source: EncryptSource,
+    /// Mnemonic, private key, intermediate code or confirmation code
+    #[clap(
+        name = "MNEMONIC|PRIVATE KEY|FILE NAME",
+        required_unless_present = "intermediate"
+    )]
+    pub source: Option<EncryptSource>,
+
+    /// Generate intermediate code of password for EC-multiply encryption [encrypt only]
+    #[clap(long, conflicts_with = "MNEMONIC|PRIVATE KEY|FILE NAME")]
+    pub intermediate: bool,
+
+    /// Lot number of intermediate code, 100000 ~ 999999
+    #[clap(
+        long,
+        requires_all = ["intermediate", "sequence"],
+        value_parser = clap::value_parser!(u32).range(100000..=999999)
+    )]
+    pub lot: Option<u32>,
+
+    /// Sequence number of intermediate code, 1 ~ 4095
+    #[clap(
+        long,
+        requires_all = ["intermediate", "lot"],
+        value_parser = clap::value_parser!(u32).range(1..=4095)
+    )]
+    pub sequence: Option<u32>,
+
+    /// Amount of encrypted keys generated from intermediate code [default: 1]
+    #[clap(short = 'm', long)]
+    pub amount: Option<u32>,
+
+    /// Disguise encrypted keys by the reserved flag bits, they decrypt as usual [encrypt only]
+    #[clap(long)]
+    pub disguise: bool,
+
+    /// Format of bulk file, values of other formats are converted in place
+    #[clap(long, value_enum, default_value_t = BulkFormat::Text)]
+    pub format: BulkFormat,
+
+    /// Convert only the column of csv/tsv (header name or number from 1), or the field of jsonl
+    #[clap(long, alias = "field", value_name = "COLUMN|FIELD")]
+    pub column: Option<String>,
+
+    /// Exit with error if any key of bulk file fails to convert
+    #[clap(long)]
+    pub strict: bool,
+
+    /// Write bulk file results to a new file instead of stdout
+    #[clap(short, long, value_name = "FILE")]
+    pub output: Option<String>,
+
+    /// Recover the password of an encrypted key from candidates [decrypt only]
+    #[clap(long, requires = "candidates", conflicts_with = "password")]
+    pub recover: bool,
+
+    /// Candidate passwords file, one password per line
+    #[clap(long, value_name = "FILE", requires = "recover")]
+    pub wordlist: Option<String>,
+
+    /// Remembered password, its typo variations are candidates
+    #[clap(long, value_name = "PASSWORD", requires = "recover")]
+    pub guess: Option<String>,
+
+    /// Verify the password by the verify word suffix of encrypted mnemonic [decrypt only]
+    #[clap(long)]
+    pub verify: bool,
+
+    /// Amount of worker threads of bulk file or recovery [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
 
     /// Password
     #[clap(hide = true, long)]
     pub password: Option<String>,
 }
 
+impl<const ENCRYPT: bool> EncryptCommand<ENCRYPT> {
+    #[inline]
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(default_jobs)
+    }
+}
+
+/// Format of bulk file
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkFormat {
+    /// Keys split by whitespaces
+    Text,
+    /// Comma separated values
+    Csv,
+    /// Tab separated values
+    Tsv,
+    /// JSON object per line
+    Jsonl,
+}
+
 /// Source of encryption/decryption
 #[derive(Clone, Debug)]
 pub enum EncryptSource {
@@ -20,6 +110,10 @@ pub enum EncryptSource {
     Key(String),
     /// Text file containing private keys or encrypted keys.
     File(String),
+    /// Intermediate code of EC-multiply encryption.
+    Intermediate(String),
+    /// Confirmation code of EC-multiply encrypted key.
+    Confirmation(String),
 }
 
 impl std::str::FromStr for EncryptSource {
@@ -28,6 +122,10 @@ impl std::str::FromStr for EncryptSource {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if is_private_key(s) || is_encrypted_key(s) {
             Ok(EncryptSource::Key(s.to_string()))
+        } else if is_intermediate_code(s) {
+            Ok(EncryptSource::Intermediate(s.to_string()))
+        } else if is_confirmation_code(s) {
+            Ok(EncryptSource::Confirmation(s.to_string()))
         } else if is_mnemonic(s) {
             Ok(EncryptSource::Mnemonic(s.to_string()))
         } else if std::path::Path::new(s).exists() {
@@ -38,7 +136,12 @@ impl std::str::FromStr for EncryptSource {
     }
 }
 
-/// "menmonic", "mnemonic; verify" or "mnemonic; count"
+/// "mnemonic", "mnemonic; verify" or "mnemonic; count"
+/// # Suffix:
+///   The verify word of encryption checks the password on decryption,
+///     its index is `(8 - original size / 3) << 8 | checksum`.
+///   The count is the desired word count of the result,
+///     no less than the mnemonic on encryption, no more on decryption.
 #[inline(always)]
 fn is_mnemonic(s: &str) -> bool {
     let count = s.split_whitespace().count();
@@ -48,8 +151,8 @@ fn is_mnemonic(s: &str) -> bool {
 #[inline(always)]
 fn is_private_key(s: &str) -> bool {
     (match s.len() {
-        51 if s.starts_with('5') => true,
-        52 if s.starts_with(['K', 'L']) => true,
+        51 if s.starts_with(['5', '9']) => true,
+        52 if s.starts_with(['K', 'L', 'c']) => true,
         _ => false,
     }) && (bitcoin::base58::decode(s).is_ok())
 }
@@ -61,7 +164,7 @@ fn is_private_key(s: &str) -> bool {
 ///   > EC-multiplied keys without compression (prefix 6Pf)
 ///   > EC-multiplied keys with compression (prefix 6Pn)
 /// # Notice:
-///   Ignore the second prefix char for disguised key.
+///   Ignore the second prefix char for disguised key (`encrypt --disguise`).
 ///   So, disguised key can be decrypted.
 #[inline(always)]
 fn is_encrypted_key(s: &str) -> bool {
@@ -0,0 +1,159 @@
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit, generic_array::GenericArray};
+use anyhow::anyhow;
+use artimonist::BIP38;
+use artimonist::bitcoin::hashes::{Hash, sha256d};
+use artimonist::bitcoin::secp256k1::Secp256k1;
+use artimonist::bitcoin::{Address, NetworkKind, PrivateKey, PublicKey, base58};
+use unicode_normalization::UnicodeNormalization;
+
+/// Prefix of encrypted keys of non-EC-multiply mode
+const PRE_NON_EC: [u8; 2] = [0x01, 0x42];
+/// Prefix of encrypted keys of EC-multiply mode
+const PRE_EC: [u8; 2] = [0x01, 0x43];
+
+const FLAG_NON_EC: u8 = 0xc0;
+pub const FLAG_COMPRESSED: u8 = 0x20;
+/// Lot and sequence numbers in owner entropy of EC-multiply mode
+pub const FLAG_LOT: u8 = 0x04;
+/// Reserved flag bits of non-EC-multiply mode, all set by disguised keys
+const FLAG_DISGUISE: u8 = 0x0f;
+
+/// Scrypt parameters (log n, r, p) of the passphrase in non-EC-multiply mode
+const SCRYPT_NON_EC: (u8, u32, u32) = (14, 8, 8);
+
+/// Networks of the address hash, mainnet is checked first
+pub const NETWORKS: [NetworkKind; 2] = [NetworkKind::Main, NetworkKind::Test];
+
+type Result<T> = anyhow::Result<T>;
+
+/// Encrypt a mainnet or testnet WIF (BIP38 non-EC-multiply mode),
+///   the address hash is of the p2pkh address on the network of the key.
+/// # Disguise:
+///   Reserved flag bits are set, so the second prefix char changes (`6PR` to `6PU`, `6PY` to `6Pb`).
+///   Decryption ignores the reserved bits, clearing them restores the standard key.
+pub fn encrypt(wif: &str, passphrase: &str, disguise: bool) -> Result<String> {
+    let key = PrivateKey::from_wif(wif).map_err(|e| anyhow!("Invalid WIF: {e}"))?;
+    let salt = address_hash(&key.public_key(&Secp256k1::new()), key.network);
+    let pass = passphrase.nfc().collect::<String>();
+    let (half1, half2) = derive_halves(pass.as_bytes(), &salt, SCRYPT_NON_EC)?;
+    let cipher = aes::Aes256::new_from_slice(&half2)?;
+
+    let secret = key.to_bytes();
+    let mut part1 = xor(&secret[..16], &half1[..16]);
+    cipher.encrypt_block(GenericArray::from_mut_slice(&mut part1));
+    let mut part2 = xor(&secret[16..32], &half1[16..32]);
+    cipher.encrypt_block(GenericArray::from_mut_slice(&mut part2));
+
+    let mut flag = match key.compressed {
+        true => FLAG_NON_EC | FLAG_COMPRESSED,
+        false => FLAG_NON_EC,
+    };
+    if disguise {
+        flag |= FLAG_DISGUISE;
+    }
+    let encrypted = [&PRE_NON_EC[..], &[flag], &salt, &part1, &part2].concat();
+    Ok(base58::encode_check(&encrypted))
+}
+
+/// Details of an encrypted key
+pub struct KeyInfo {
+    pub ec_multiply: bool,
+    pub compressed: bool,
+    /// Lot and sequence numbers of EC-multiply mode
+    pub lot_sequence: Option<(u32, u32)>,
+    pub address_hash: [u8; 4],
+    pub disguised: bool,
+    /// The key with reserved flag bits cleared
+    pub standard: String,
+}
+
+/// Inspect an encrypted key of either mode, reserved flag bits tell a disguised key.
+pub fn inspect(encrypted: &str) -> Result<KeyInfo> {
+    let mut data = base58::decode_check(encrypted).map_err(|e| anyhow!("Base58 error: {e}"))?;
+    if data.len() != 39 {
+        return Err(anyhow!("Invalid encrypted key"));
+    }
+    let flag = data[2];
+    let known = match [data[0], data[1]] {
+        PRE_NON_EC => FLAG_NON_EC | FLAG_COMPRESSED,
+        PRE_EC => FLAG_COMPRESSED | FLAG_LOT,
+        _ => return Err(anyhow!("Invalid encrypted key")),
+    };
+    let ec_multiply = data[..2] == PRE_EC;
+    let lot_sequence = (ec_multiply && flag & FLAG_LOT != 0).then(|| {
+        let number = u32::from_be_bytes([data[11], data[12], data[13], data[14]]);
+        (number >> 12, number & 0xfff)
+    });
+    data[2] &= known;
+    Ok(KeyInfo {
+        ec_multiply,
+        compressed: flag & FLAG_COMPRESSED != 0,
+        lot_sequence,
+        address_hash: [data[3], data[4], data[5], data[6]],
+        disguised: flag != data[2],
+        standard: base58::encode_check(&data),
+    })
+}
+
+/// Decrypt an encrypted key of either mode,
+///   the WIF is of the network whose address hash matches,
+///   EC-multiply keys are decrypted by the library with mainnet address hash.
+pub fn decrypt(encrypted: &str, passphrase: &str) -> Result<String> {
+    let data = base58::decode_check(encrypted).map_err(|e| anyhow!("Base58 error: {e}"))?;
+    if data.len() != 39 {
+        return Err(anyhow!("Invalid encrypted key"));
+    }
+    match [data[0], data[1]] {
+        PRE_NON_EC => Ok(decrypt_non_ec(&data, passphrase)?.to_wif()),
+        PRE_EC => Ok(encrypted.bip38_decrypt(passphrase)?),
+        _ => Err(anyhow!("Invalid encrypted key")),
+    }
+}
+
+fn decrypt_non_ec(data: &[u8], passphrase: &str) -> Result<PrivateKey> {
+    let (flag, salt) = (data[2], &data[3..7]);
+    let pass = passphrase.nfc().collect::<String>();
+    let (half1, half2) = derive_halves(pass.as_bytes(), salt, SCRYPT_NON_EC)?;
+    let cipher = aes::Aes256::new_from_slice(&half2)?;
+
+    let mut part1 = data[7..23].to_vec();
+    cipher.decrypt_block(GenericArray::from_mut_slice(&mut part1));
+    let mut part2 = data[23..39].to_vec();
+    cipher.decrypt_block(GenericArray::from_mut_slice(&mut part2));
+    let secret = [xor(&part1, &half1[..16]), xor(&part2, &half1[16..32])].concat();
+
+    let mut key = PrivateKey::from_slice(&secret, NetworkKind::Main)?;
+    key.compressed = flag & FLAG_COMPRESSED != 0;
+    let pub_key = key.public_key(&Secp256k1::new());
+    key.network = NETWORKS
+        .into_iter()
+        .find(|&network| address_hash(&pub_key, network) == salt)
+        .ok_or(anyhow!("Invalid passphrase"))?;
+    Ok(key)
+}
+
+/// First 4 bytes of double sha256 of the p2pkh address
+pub fn address_hash(pub_key: &PublicKey, network: NetworkKind) -> [u8; 4] {
+    let address = Address::p2pkh(pub_key, network).to_string();
+    let hash = sha256d::Hash::hash(address.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Derived half1 and half2 of scrypt, by the passphrase in non-EC-multiply mode
+///   or by the pass point in EC-multiply mode
+pub fn derive_halves(
+    password: &[u8],
+    salt: &[u8],
+    (log_n, r, p): (u8, u32, u32),
+) -> Result<([u8; 32], [u8; 32])> {
+    let mut derived = [0u8; 64];
+    let params = scrypt::Params::new(log_n, r, p, 64).map_err(|e| anyhow!("{e}"))?;
+    scrypt::scrypt(password, salt, &params, &mut derived).map_err(|e| anyhow!("{e}"))?;
+    let (half1, half2) = derived.split_at(32);
+    Ok((half1.try_into()?, half2.try_into()?))
+}
+
+#[inline(always)]
+pub fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
@@ -0,0 +1,262 @@
+use super::arg::BulkFormat;
+use super::format::{Column, spans};
+use super::{EncryptCommand, bip38};
+use crate::utils::{Progress, ordered_for_each};
+use anyhow::anyhow;
+use artimonist::{Mnemonic, MnemonicEncryption};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Tokens of bulk file: converted count, line numbers of skipped and failed tokens
+#[derive(Default)]
+struct Report {
+    converted: usize,
+    skipped: Vec<usize>,
+    failed: Vec<usize>,
+}
+
+/// Conversion of a single token
+enum Token {
+    /// Not a key, keep it
+    Plain,
+    /// Key of the other direction, keep it
+    Skipped,
+    Converted(String),
+    /// Wrong password or corrupt key, keep it
+    Failed,
+}
+
+/// Suffix of a mnemonic line
+enum Suffix {
+    None,
+    /// Verify word of an encrypted mnemonic
+    Verify,
+    /// Desired word count of the result, converts in both directions
+    Count,
+}
+
+impl<const ENCRYPT: bool> EncryptCommand<ENCRYPT> {
+    /// Convert keys of the file line by line, then report on stderr
+    pub(super) fn execute_bulk(&self, file: &str, password: &str) -> anyhow::Result<()> {
+        let mut f: BufWriter<Box<dyn Write>> = match &self.output {
+            Some(output) => {
+                if std::path::Path::new(output).exists()
+                    && std::fs::canonicalize(output)? == std::fs::canonicalize(file)?
+                {
+                    return Err(anyhow!("Output file must differ from the source file"));
+                }
+                BufWriter::new(Box::new(File::create(output)?))
+            }
+            None => BufWriter::new(Box::new(std::io::stdout())),
+        };
+
+        let format = self.format;
+        if format == BulkFormat::Text && self.column.is_some() {
+            return Err(anyhow!("Column requires csv, tsv or jsonl format"));
+        }
+        let mut column = Column::new(format, self.column.as_deref());
+
+        // lines keep their endings, header of csv/tsv names the columns
+        let mut lines = read_lines(file)?;
+        if matches!(column, Column::Name(_)) && format != BulkFormat::Jsonl && !lines.is_empty() {
+            let (_, header) = lines.remove(0);
+            column = column.resolve(format, header.trim_end_matches(['\r', '\n']))?;
+            write!(f, "{header}")?;
+        }
+
+        // convert lines on worker threads, write them in order
+        let mut report = Report::default();
+        let mut progress = Progress::new(lines.len() as u64);
+        let convert_line = |(_, buf): &(usize, String)| {
+            let line = buf.trim_end_matches(['\r', '\n']);
+            Ok(tokens::<ENCRYPT>(
+                format,
+                &column,
+                line,
+                password,
+                self.disguise,
+            ))
+        };
+        let write_line = |(i, buf): &(usize, String),
+                          tokens: Option<Vec<(Range<usize>, Token)>>| {
+            progress.inc(1);
+            // keep line endings as they are
+            let line = buf.trim_end_matches(['\r', '\n']);
+            let ending = &buf[line.len()..];
+            let Some(tokens) = tokens else {
+                // unparsable line is kept and reported
+                report.failed.push(i + 1);
+                write!(f, "{buf}")?;
+                return Ok(());
+            };
+            let mut new_line = String::with_capacity(buf.len());
+            let mut end = 0;
+            for (span, token) in tokens {
+                let key = match token {
+                    Token::Plain => continue,
+                    Token::Converted(key) => {
+                        report.converted += 1;
+                        key
+                    }
+                    Token::Skipped => {
+                        report.skipped.push(i + 1);
+                        continue;
+                    }
+                    Token::Failed => {
+                        report.failed.push(i + 1);
+                        continue;
+                    }
+                };
+                new_line += &line[end..span.start];
+                new_line += &key;
+                end = span.end;
+            }
+            new_line += &line[end..];
+            write!(f, "{new_line}{ending}")?;
+            Ok(())
+        };
+        ordered_for_each(&lines, self.jobs(), convert_line, write_line)?;
+        progress.finish();
+        f.flush()?;
+
+        report.print();
+        if self.strict && !report.failed.is_empty() {
+            return Err(anyhow!("{} keys failed to convert", report.failed.len()));
+        }
+        Ok(())
+    }
+}
+
+/// Numbered lines of the file, line endings are kept
+fn read_lines(file: &str) -> anyhow::Result<Vec<(usize, String)>> {
+    let mut reader = BufReader::new(File::open(file)?);
+    let mut lines = vec![];
+    for i in 0.. {
+        let mut buf = String::new();
+        if reader.read_line(&mut buf)? == 0 {
+            break;
+        }
+        lines.push((i, buf));
+    }
+    Ok(lines)
+}
+
+/// Conversions of the keys in a line, `None` if the line is unparsable
+fn tokens<const ENCRYPT: bool>(
+    format: BulkFormat,
+    column: &Column,
+    line: &str,
+    password: &str,
+    disguise: bool,
+) -> Option<Vec<(Range<usize>, Token)>> {
+    // a mnemonic line converts as a whole, its label is kept
+    if let Some((span, suffix)) = mnemonic_span(format, line) {
+        let token = convert_mnemonic::<ENCRYPT>(&line[span.clone()], suffix, password);
+        return Some(vec![(span, token)]);
+    }
+    let spans = spans(format, line, column).ok()?;
+    Some(
+        spans
+            .into_iter()
+            .map(|span| {
+                let token = convert::<ENCRYPT>(&line[span.clone()], password, disguise);
+                (span, token)
+            })
+            .collect(),
+    )
+}
+
+fn convert<const ENCRYPT: bool>(s: &str, password: &str, disguise: bool) -> Token {
+    let result = match (s.is_private(), s.is_encrypted()) {
+        (true, _) if ENCRYPT => bip38::encrypt(s, password, disguise),
+        (_, true) if !ENCRYPT => bip38::decrypt(s, password),
+        (true, _) | (_, true) => return Token::Skipped,
+        _ => return Token::Plain,
+    };
+    match result {
+        Ok(key) => Token::Converted(key),
+        Err(_) => Token::Failed,
+    }
+}
+
+/// Mnemonic of a text line: `words`, `words; verify`, `words; count` or `name: words`,
+///   returns the span of the mnemonic and its suffix.
+fn mnemonic_span(format: BulkFormat, line: &str) -> Option<(Range<usize>, Suffix)> {
+    if format != BulkFormat::Text {
+        return None;
+    }
+    let start = line.rfind(':').map_or(0, |i| i + 1);
+    let rest = &line[start..];
+    let start = start + rest.len() - rest.trim_start().len();
+    let end = start + rest.trim().len();
+    let (words, suffix) = match line[start..end].rsplit_once(';') {
+        Some((words, suffix)) => (words, Some(suffix.trim())),
+        None => (&line[start..end], None),
+    };
+    Mnemonic::from_str(words).ok()?;
+    // `; N` is the desired word count of the result, otherwise a verify word
+    let suffix = match suffix {
+        None => Suffix::None,
+        Some(s) if s.parse::<u8>().is_ok() => Suffix::Count,
+        Some(_) => Suffix::Verify,
+    };
+    Some((start..end, suffix))
+}
+
+fn convert_mnemonic<const ENCRYPT: bool>(s: &str, suffix: Suffix, password: &str) -> Token {
+    let result = match suffix {
+        Suffix::None | Suffix::Count if ENCRYPT => s.mnemonic_encrypt(password),
+        Suffix::Verify | Suffix::Count if !ENCRYPT => s.mnemonic_decrypt(password),
+        _ => return Token::Skipped,
+    };
+    match result {
+        Ok(mnemonic) => Token::Converted(mnemonic),
+        Err(_) => Token::Failed,
+    }
+}
+
+impl Report {
+    fn print(&self) {
+        let lines = |numbers: &[usize]| {
+            let mut numbers = numbers.to_vec();
+            numbers.dedup();
+            numbers
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        eprintln!(
+            "Converted: {}, Skipped: {}, Failed: {}",
+            self.converted,
+            self.skipped.len(),
+            self.failed.len()
+        );
+        if !self.skipped.is_empty() {
+            eprintln!("Skipped lines: {}", lines(&self.skipped));
+        }
+        if !self.failed.is_empty() {
+            eprintln!("Failed lines: {}", lines(&self.failed));
+        }
+    }
+}
+
+trait Bip38 {
+    fn is_private(&self) -> bool;
+    fn is_encrypted(&self) -> bool;
+}
+
+impl Bip38 for str {
+    #[inline(always)]
+    fn is_private(&self) -> bool {
+        (self.starts_with(['K', 'L', 'c']) && self.len() == 52)
+            || (self.starts_with(['5', '9']) && self.len() == 51)
+    }
+
+    #[inline(always)]
+    fn is_encrypted(&self) -> bool {
+        self.starts_with("6P") && self.len() == 58
+    }
+}
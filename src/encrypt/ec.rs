@@ -0,0 +1,163 @@
+use super::bip38::{self, FLAG_COMPRESSED, FLAG_LOT, NETWORKS, derive_halves, xor};
+use aes::cipher::{BlockDecrypt, BlockEncrypt, KeyInit, generic_array::GenericArray};
+use anyhow::anyhow;
+use artimonist::BIP38;
+use artimonist::bitcoin::hashes::{Hash, sha256d};
+use artimonist::bitcoin::secp256k1::{Scalar, Secp256k1, SecretKey};
+use artimonist::bitcoin::{Address, NetworkKind, PublicKey, base58};
+use rand::RngCore;
+use unicode_normalization::UnicodeNormalization;
+
+/// Prefix of intermediate codes, with or without lot and sequence
+const PRE_PASS_LOT: [u8; 8] = [0x2c, 0xe9, 0xb3, 0xe1, 0xff, 0x39, 0xe2, 0x51];
+const PRE_PASS: [u8; 8] = [0x2c, 0xe9, 0xb3, 0xe1, 0xff, 0x39, 0xe2, 0x53];
+/// Prefix of confirmation codes
+const PRE_CONFIRM: [u8; 5] = [0x64, 0x3b, 0xf6, 0xa8, 0x9a];
+
+/// Lot flag set by the library instead of `FLAG_LOT`
+const FLAG_LOT_LIBRARY: u8 = 0x40;
+/// Scrypt parameters (log n, r, p) of the pass point
+const SCRYPT_EC: (u8, u32, u32) = (10, 1, 1);
+
+type Result<T> = anyhow::Result<T>;
+
+/// Encrypted key generated from an intermediate code (BIP38 EC-multiply mode)
+/// # Reference:
+///   <https://github.com/bitcoin/bips/blob/master/bip-0038.mediawiki>
+pub struct EcKey {
+    pub address: String,
+    pub encrypted: String,
+    pub confirmation: String,
+}
+
+#[inline(always)]
+pub fn is_intermediate_code(s: &str) -> bool {
+    s.starts_with("passphrase") && s.len() == 72 && base58::decode(s).is_ok()
+}
+
+#[inline(always)]
+pub fn is_confirmation_code(s: &str) -> bool {
+    s.starts_with("cfrm38") && s.len() == 75 && base58::decode(s).is_ok()
+}
+
+/// Generate a compressed encrypted key and its confirmation code, the passphrase is not required.
+///   The library encrypts the key of the seed, the confirmation code is made of the same seed.
+pub fn generate(intermediate: &str) -> Result<EcKey> {
+    let code = base58::decode_check(intermediate)?;
+    if code.len() != 49 || !(code[..8] == PRE_PASS_LOT || code[..8] == PRE_PASS) {
+        return Err(anyhow!("Invalid intermediate code"));
+    }
+    let (owner_entropy, pass_point) = (&code[8..16], &code[16..49]);
+
+    let mut seed = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut seed);
+    let mut encrypted = base58::decode_check(&generate_ec_key::<str>(seed, intermediate)?)?;
+    if encrypted[2] & FLAG_LOT_LIBRARY != 0 {
+        encrypted[2] = encrypted[2] & !FLAG_LOT_LIBRARY | FLAG_LOT;
+    }
+    let (flag, address_hash) = (encrypted[2], &encrypted[3..7]);
+
+    // point b = factor * G, encrypted for the passphrase owner
+    let secp = Secp256k1::new();
+    let factor = sha256d::Hash::hash(&seed).to_byte_array();
+    let point = PublicKey::from_slice(pass_point)?
+        .inner
+        .mul_tweak(&secp, &Scalar::from_be_bytes(factor)?)?;
+    let address = Address::p2pkh(PublicKey::new(point), NetworkKind::Main).to_string();
+    let (half1, half2) = derive_halves(
+        pass_point,
+        &[address_hash, owner_entropy].concat(),
+        SCRYPT_EC,
+    )?;
+    let cipher = aes::Aes256::new_from_slice(&half2)?;
+    let point_b = SecretKey::from_slice(&factor)?
+        .public_key(&secp)
+        .serialize();
+    let mut x1 = xor(&point_b[1..17], &half1[..16]);
+    cipher.encrypt_block(GenericArray::from_mut_slice(&mut x1));
+    let mut x2 = xor(&point_b[17..33], &half1[16..32]);
+    cipher.encrypt_block(GenericArray::from_mut_slice(&mut x2));
+    let confirmation = [
+        &PRE_CONFIRM[..],
+        &[flag],
+        address_hash,
+        owner_entropy,
+        &[point_b[0] ^ (half2[31] & 0x01)],
+        &x1,
+        &x2,
+    ]
+    .concat();
+
+    Ok(EcKey {
+        address,
+        encrypted: base58::encode_check(&encrypted),
+        confirmation: base58::encode_check(&confirmation),
+    })
+}
+
+/// Encrypted key of the seed by the library, whose EC-multiply trait is only reachable by `BIP38`
+#[inline(always)]
+fn generate_ec_key<S: BIP38 + ?Sized>(seed: [u8; 24], intermediate: &str) -> Result<String> {
+    Ok(S::generate_ec_key(seed, intermediate)?)
+}
+
+/// Verify the confirmation code by the passphrase, returns the address of the encrypted key.
+pub fn confirm(confirmation: &str, passphrase: &str) -> Result<String> {
+    let code = base58::decode_check(confirmation)?;
+    if code.len() != 51 || code[..5] != PRE_CONFIRM {
+        return Err(anyhow!("Invalid confirmation code"));
+    }
+    let (flag, address_hash) = (code[5], &code[6..10]);
+    let (owner_entropy, encrypted_point) = (&code[10..18], &code[18..51]);
+
+    let secp = Secp256k1::new();
+    let pass_factor = pass_factor(passphrase, owner_entropy, flag & FLAG_LOT != 0)?;
+    let pass_point = SecretKey::from_slice(&pass_factor)?
+        .public_key(&secp)
+        .serialize();
+    let salt = [address_hash, owner_entropy].concat();
+    let (half1, half2) = derive_halves(&pass_point, &salt, SCRYPT_EC)?;
+    let cipher = aes::Aes256::new_from_slice(&half2)?;
+
+    let mut x1 = encrypted_point[1..17].to_vec();
+    cipher.decrypt_block(GenericArray::from_mut_slice(&mut x1));
+    let mut x2 = encrypted_point[17..33].to_vec();
+    cipher.decrypt_block(GenericArray::from_mut_slice(&mut x2));
+    let point_b = [
+        &[encrypted_point[0] ^ (half2[31] & 0x01)][..],
+        &xor(&x1, &half1[..16]),
+        &xor(&x2, &half1[16..32]),
+    ]
+    .concat();
+
+    let point = PublicKey::from_slice(&point_b)
+        .map_err(|_| anyhow!("Invalid password"))?
+        .inner
+        .mul_tweak(&secp, &Scalar::from_be_bytes(pass_factor)?)?;
+    let pub_key = PublicKey {
+        compressed: flag & FLAG_COMPRESSED != 0,
+        inner: point,
+    };
+    NETWORKS
+        .into_iter()
+        .find(|&network| bip38::address_hash(&pub_key, network) == address_hash)
+        .map(|network| Address::p2pkh(pub_key, network).to_string())
+        .ok_or(anyhow!("Invalid password"))
+}
+
+/// Pass factor of passphrase, lot and sequence take the last 4 bytes of owner entropy
+fn pass_factor(passphrase: &str, owner_entropy: &[u8], lot: bool) -> Result<[u8; 32]> {
+    let pass = passphrase.nfc().collect::<String>();
+    let salt = if lot {
+        &owner_entropy[..4]
+    } else {
+        owner_entropy
+    };
+    let mut factor = [0u8; 32];
+    let params = scrypt::Params::new(14, 8, 8, 32).map_err(|e| anyhow!("{e}"))?;
+    scrypt::scrypt(pass.as_bytes(), salt, &params, &mut factor).map_err(|e| anyhow!("{e}"))?;
+    if lot {
+        factor = sha256d::Hash::hash(&[&factor[..], owner_entropy].concat()).to_byte_array();
+    }
+    Ok(factor)
+}
@@ -1,14 +1,59 @@
-use super::{EncryptCommand, arg::EncryptSource};
+use super::{EncryptCommand, arg::EncryptSource, bip38, ec};
 use crate::{Execute, utils::inquire_password};
 use anyhow::anyhow;
 use artimonist::{BIP38, MnemonicEncryption};
-use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::io::{BufWriter, Write};
 
 impl<const ENCRYPT: bool> Execute for EncryptCommand<ENCRYPT> {
     fn execute(&mut self) -> anyhow::Result<()> {
-        if !artimonist::NETWORK.is_mainnet() {
-            return Err(anyhow!("encrypt/decrypt is only available on mainnet"));
+        if !ENCRYPT && self.intermediate {
+            return Err(anyhow!("Intermediate code is generated by encrypt"));
+        }
+        if !ENCRYPT && matches!(self.source, Some(EncryptSource::Intermediate(_))) {
+            return Err(anyhow!("Intermediate code can only be used by encrypt"));
+        }
+        if ENCRYPT && matches!(self.source, Some(EncryptSource::Confirmation(_))) {
+            return Err(anyhow!("Confirmation code can only be verified by decrypt"));
+        }
+        if self.disguise
+            && (!ENCRYPT || matches!(self.source, None | Some(EncryptSource::Intermediate(_))))
+        {
+            return Err(anyhow!("Disguise is only for encrypting private keys"));
+        }
+        if self.recover && (ENCRYPT || !matches!(self.source, Some(EncryptSource::Key(_)))) {
+            return Err(anyhow!("Recover is only for decrypting encrypted keys"));
+        }
+        if self.verify && (ENCRYPT || !matches!(self.source, Some(EncryptSource::Mnemonic(_)))) {
+            return Err(anyhow!("Verify is only for decrypting encrypted mnemonics"));
+        }
+        if self.amount.is_some() && !matches!(self.source, Some(EncryptSource::Intermediate(_))) {
+            return Err(anyhow!(
+                "Amount is only for generating keys from intermediate code"
+            ));
+        }
+        if self.output.is_some() && !matches!(self.source, Some(EncryptSource::File(_))) {
+            return Err(anyhow!("Output file is only for bulk files"));
+        }
+
+        // generating keys from intermediate code does not require the password
+        if let Some(EncryptSource::Intermediate(code)) = &self.source {
+            let mut f = BufWriter::new(std::io::stdout());
+            for _ in 0..self.amount.unwrap_or(1) {
+                let key = ec::generate(code)?;
+                writeln!(
+                    f,
+                    "{}, {}, {}",
+                    key.address, key.encrypted, key.confirmation
+                )?;
+            }
+            return Ok(());
+        }
+
+        // candidate passwords are tested instead of the password
+        if self.recover
+            && let Some(EncryptSource::Key(key)) = &self.source
+        {
+            return self.execute_recover(key);
         }
 
         // if no password is provided, prompt for it
@@ -17,73 +62,36 @@ impl<const ENCRYPT: bool> Execute for EncryptCommand<ENCRYPT> {
             None => inquire_password(false)?,
         };
 
-        match &self.source {
+        let Some(source) = &self.source else {
+            let (lot, sequence) = (self.lot.unwrap_or(0), self.sequence.unwrap_or(0));
+            println!("{}", password.bip38_ec_factor(lot, sequence)?);
+            return Ok(());
+        };
+        match source {
             EncryptSource::Mnemonic(str) => {
                 if ENCRYPT {
                     println!("{}", str.mnemonic_encrypt(&password)?);
+                } else if self.verify {
+                    self.execute_verify(str, &password)?;
                 } else {
                     println!("{}", str.mnemonic_decrypt(&password)?);
                 }
             }
             EncryptSource::Key(key) => {
                 if ENCRYPT {
-                    println!("{}", key.bip38_encrypt(&password)?);
+                    println!("{}", bip38::encrypt(key, &password, self.disguise)?);
                 } else {
-                    println!("{}", key.bip38_decrypt(&password)?);
+                    println!("{}", bip38::decrypt(key, &password)?);
                 }
             }
             EncryptSource::File(file) => {
-                execute_bulk::<ENCRYPT>(file, &password)?;
+                self.execute_bulk(file, &password)?;
+            }
+            EncryptSource::Confirmation(code) => {
+                println!("{}", ec::confirm(code, &password)?);
             }
+            EncryptSource::Intermediate(_) => unreachable!(),
         }
         Ok(())
     }
 }
-
-fn execute_bulk<const ENCRYPT: bool>(file: &str, password: &str) -> anyhow::Result<()> {
-    let f = &mut BufWriter::new(std::io::stdout());
-    for ln in BufReader::new(File::open(file)?).lines() {
-        let line = ln?;
-        if line
-            .split_ascii_whitespace()
-            .any(|s| (ENCRYPT && s.is_private()) || (!ENCRYPT && s.is_encrypted()))
-        {
-            let new_line = line
-                .split_ascii_whitespace()
-                .map(|s| {
-                    if ENCRYPT && s.is_private() {
-                        s.bip38_encrypt(password).unwrap_or(s.to_string())
-                    } else if s.is_encrypted() {
-                        s.bip38_decrypt(password).unwrap_or(s.to_string())
-                    } else {
-                        s.to_string()
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" ");
-            writeln!(f, "{new_line}")?;
-            f.flush()?;
-        } else {
-            writeln!(f, "{line}")?;
-        }
-    }
-    Ok(())
-}
-
-trait Bip38 {
-    fn is_private(&self) -> bool;
-    fn is_encrypted(&self) -> bool;
-}
-
-impl Bip38 for str {
-    #[inline(always)]
-    fn is_private(&self) -> bool {
-        (self.starts_with(['K', 'L']) && self.len() == 52)
-            || (self.starts_with('5') && self.len() == 51)
-    }
-
-    #[inline(always)]
-    fn is_encrypted(&self) -> bool {
-        self.starts_with("6P") && self.len() == 58
-    }
-}
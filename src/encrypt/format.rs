@@ -0,0 +1,147 @@
+use super::arg::BulkFormat;
+use anyhow::anyhow;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Column selector of bulk file
+pub enum Column {
+    /// Convert all columns
+    All,
+    /// Column index from 0
+    Index(usize),
+    /// Column name of csv/tsv header, or field name of jsonl
+    Name(String),
+}
+
+impl Column {
+    /// Column by number from 1 or by name, field by name
+    pub fn new(format: BulkFormat, column: Option<&str>) -> Self {
+        match column {
+            None => Column::All,
+            Some(s) if format == BulkFormat::Jsonl => Column::Name(s.to_string()),
+            Some(s) => match s.parse::<usize>() {
+                Ok(n) if n > 0 => Column::Index(n - 1),
+                _ => Column::Name(s.to_string()),
+            },
+        }
+    }
+
+    /// Resolve column name by the csv/tsv header line
+    pub fn resolve(self, format: BulkFormat, header: &str) -> anyhow::Result<Self> {
+        match self {
+            Column::Name(name) if matches!(format, BulkFormat::Csv | BulkFormat::Tsv) => {
+                let index = spans(format, header, &Column::All)?
+                    .iter()
+                    .position(|span| header[span.clone()] == name)
+                    .ok_or(anyhow!("Column not found: {name}"))?;
+                Ok(Column::Index(index))
+            }
+            column => Ok(column),
+        }
+    }
+
+    #[inline(always)]
+    fn select(&self, index: usize) -> bool {
+        match self {
+            Column::All => true,
+            Column::Index(i) => *i == index,
+            Column::Name(_) => false,
+        }
+    }
+}
+
+/// Byte ranges of the selected values in a line, other bytes are kept as they are
+pub fn spans(format: BulkFormat, line: &str, column: &Column) -> anyhow::Result<Vec<Range<usize>>> {
+    let spans = match format {
+        BulkFormat::Text => text_tokens(line),
+        BulkFormat::Tsv => tsv_cells(line),
+        BulkFormat::Csv => csv_cells(line),
+        BulkFormat::Jsonl => return json_fields(line, column),
+    };
+    Ok(spans
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| column.select(*i))
+        .map(|(_, span)| span)
+        .collect())
+}
+
+/// Tokens split by ascii whitespaces
+fn text_tokens(line: &str) -> Vec<Range<usize>> {
+    let (mut spans, mut start) = (vec![], None);
+    for (i, c) in line.char_indices() {
+        match (c.is_ascii_whitespace(), start) {
+            (true, Some(j)) => {
+                spans.push(j..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    spans.extend(start.map(|j| j..line.len()));
+    spans
+}
+
+fn tsv_cells(line: &str) -> Vec<Range<usize>> {
+    let mut start = 0;
+    let mut spans = vec![];
+    for (i, _) in line.match_indices('\t') {
+        spans.push(start..i);
+        start = i + 1;
+    }
+    spans.push(start..line.len());
+    spans
+}
+
+/// Cells of a csv line, quoted cells without the quotes
+fn csv_cells(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let (mut spans, mut start, mut quoted) = (vec![], 0, false);
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b',' if !quoted => {
+                spans.push(unquote(line, start..i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    spans.push(unquote(line, start..line.len()));
+    spans
+}
+
+#[inline(always)]
+fn unquote(line: &str, span: Range<usize>) -> Range<usize> {
+    let cell = &line[span.clone()];
+    match cell.len() >= 2 && cell.starts_with('"') && cell.ends_with('"') {
+        true => span.start + 1..span.end - 1,
+        false => span,
+    }
+}
+
+/// String values of top level fields, without the quotes
+fn json_fields(line: &str, column: &Column) -> anyhow::Result<Vec<Range<usize>>> {
+    if line.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let fields: HashMap<String, &RawValue> = serde_json::from_str(line)?;
+    let mut spans = fields
+        .into_iter()
+        .filter(|(name, _)| match column {
+            Column::Name(field) => name == field,
+            _ => true,
+        })
+        .map(|(_, value)| value.get())
+        // strings without escapes only, keys never have them
+        .filter(|value| value.len() >= 2 && value.starts_with('"') && !value.contains('\\'))
+        .map(|value| {
+            let start = value.as_ptr() as usize - line.as_ptr() as usize;
+            start + 1..start + value.len() - 1
+        })
+        .collect::<Vec<_>>();
+    spans.sort_by_key(|span| span.start);
+    Ok(spans)
+}
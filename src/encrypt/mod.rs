@@ -1,4 +1,11 @@
 mod arg;
+mod bip38;
+mod bulk;
+mod ec;
 mod execute;
+mod format;
+mod recover;
+mod verify;
 
 pub use arg::EncryptCommand;
+pub use bip38::{encrypt as encrypt_key, inspect as inspect_key};
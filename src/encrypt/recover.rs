@@ -0,0 +1,94 @@
+use super::{EncryptCommand, bip38};
+use crate::utils::{Progress, parallel_find};
+use anyhow::anyhow;
+use std::collections::HashSet;
+
+impl<const ENCRYPT: bool> EncryptCommand<ENCRYPT> {
+    /// Test candidate passwords of the encrypted key on worker threads,
+    ///   the first password matching the address hash is the result.
+    pub(super) fn execute_recover(&self, key: &str) -> anyhow::Result<()> {
+        bip38::inspect(key)?;
+        let candidates = match (&self.wordlist, &self.guess) {
+            (Some(file), _) => std::fs::read_to_string(file)?
+                .lines()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            (_, Some(guess)) => typos(guess),
+            (None, None) => return Err(anyhow!("Recover requires --wordlist or --guess")),
+        };
+
+        // test candidates batch by batch, stop at the first match
+        let mut progress = Progress::new(candidates.len() as u64);
+        let found = parallel_find(
+            &candidates,
+            self.jobs(),
+            |password| bip38::decrypt(key, password).ok(),
+            |password, wif| {
+                progress.inc(1);
+                Ok(wif.map(|wif| (password, wif)))
+            },
+        )?;
+        progress.finish();
+        if let Some((password, wif)) = found {
+            println!("Password: {password}");
+            println!("Private key: {wif}");
+            return Ok(());
+        }
+        Err(anyhow!(
+            "No password found in {} candidates",
+            candidates.len()
+        ))
+    }
+}
+
+/// Typo variations of the remembered password, the password itself comes first:
+///   case flips, a missing char, swapped adjacent chars and appended digits.
+fn typos(guess: &str) -> Vec<String> {
+    let chars = guess.chars().collect::<Vec<_>>();
+    let mut candidates = vec![
+        guess.to_string(),
+        guess.to_lowercase(),
+        guess.to_uppercase(),
+        chars.iter().copied().map(flip_case).collect(),
+    ];
+    for i in 0..chars.len() {
+        let mut flipped = chars.clone();
+        flipped[i] = flip_case(flipped[i]);
+        candidates.push(flipped.into_iter().collect());
+    }
+    for i in 0..chars.len() {
+        let mut missing = chars.clone();
+        missing.remove(i);
+        candidates.push(missing.into_iter().collect());
+    }
+    for i in 1..chars.len() {
+        let mut swapped = chars.clone();
+        swapped.swap(i - 1, i);
+        candidates.push(swapped.into_iter().collect());
+    }
+    for n in 0..10 {
+        candidates.push(format!("{guess}{n}"));
+    }
+    for n in 0..100 {
+        candidates.push(format!("{guess}{n:02}"));
+    }
+
+    let mut seen = HashSet::new();
+    candidates.retain(|s| !s.is_empty() && seen.insert(s.clone()));
+    candidates
+}
+
+/// Flip the case of a letter, multi-char case mappings are kept as they are
+#[inline(always)]
+fn flip_case(c: char) -> char {
+    let flipped = match c.is_lowercase() {
+        true => c.to_uppercase().collect::<Vec<_>>(),
+        false => c.to_lowercase().collect::<Vec<_>>(),
+    };
+    match flipped[..] {
+        [f] => f,
+        _ => c,
+    }
+}
@@ -0,0 +1,76 @@
+use super::EncryptCommand;
+use anyhow::anyhow;
+use artimonist::{Mnemonic, MnemonicEncryption};
+use std::str::FromStr;
+
+type Result<T> = anyhow::Result<T>;
+
+/// Suffix of an encrypted mnemonic
+enum Suffix {
+    /// Verify word and its index in the wordlist
+    Word(String, usize),
+    /// Desired word count of the decrypted mnemonic
+    Count(usize),
+    None,
+}
+
+impl<const ENCRYPT: bool> EncryptCommand<ENCRYPT> {
+    /// Decrypt the mnemonic, the verify word checks the checksum of the result,
+    ///   a wrong password still decrypts to a valid mnemonic, only the checksum tells.
+    pub(super) fn execute_verify(&self, encrypted: &str, password: &str) -> Result<()> {
+        let (mnemonic, suffix) = split_suffix(encrypted)?;
+        let (word, index) = match suffix {
+            Suffix::Word(word, index) => (word, index),
+            Suffix::Count(n) => {
+                println!("Suffix: \"{n}\" is the desired word count of the decrypted mnemonic");
+                return Err(anyhow!("No verify word to check the password"));
+            }
+            Suffix::None => {
+                println!("Suffix: none, the decrypted mnemonic has the same word count");
+                return Err(anyhow!("No verify word to check the password"));
+            }
+        };
+
+        // verify word index = (8 - original size / 3) << 8 | checksum
+        let (size, expected) = ((8 - (index >> 8)) * 3, index & 0xff);
+        println!("Suffix: \"{word}\" is the verify word of index {index}");
+        println!("  Original size: {size} words, (8 - (index >> 8)) * 3");
+        println!("  Checksum: {expected}, index & 0xff, hash of the original mnemonic");
+
+        // decrypting by the word count skips the checksum,
+        //   if only the verify word fails, the password does not match
+        let original = match encrypted.mnemonic_decrypt(password) {
+            Ok(original) => original,
+            Err(e) => match format!("{mnemonic}; {size}").mnemonic_decrypt(password) {
+                Ok(_) => {
+                    return Err(anyhow!(
+                        "Password mismatch: checksum of the decrypted mnemonic differs from {expected} of the verify word"
+                    ));
+                }
+                Err(_) => return Err(e.into()),
+            },
+        };
+        println!("Verified: {original}");
+        Ok(())
+    }
+}
+
+/// Mnemonic and suffix of the encrypted mnemonic, the last word of "mnemonic; verify" or "mnemonic; count".
+fn split_suffix(s: &str) -> Result<(String, Suffix)> {
+    let words = s.split_whitespace().collect::<Vec<_>>();
+    if Mnemonic::VALID_SIZES.contains(&words.len()) {
+        return Ok((words.join(" "), Suffix::None));
+    }
+    let Some((last, words)) = words.split_last() else {
+        return Err(anyhow!("Invalid mnemonic"));
+    };
+    let mnemonic = words.join(" ").trim_end_matches(';').to_string();
+    let language = Mnemonic::from_str(&mnemonic)?.language();
+
+    let suffix = match (language.index_of(last), last.parse::<usize>()) {
+        (Some(index), _) if index >> 8 < 5 => Suffix::Word(last.to_string(), index),
+        (_, Ok(n)) if Mnemonic::VALID_SIZES.contains(&n) => Suffix::Count(n),
+        _ => return Err(anyhow!("Invalid suffix: {last}")),
+    };
+    Ok((mnemonic, suffix))
+}
@@ -0,0 +1,45 @@
+use crate::derive::MasterKey;
+use crate::utils::default_jobs;
+
+#[derive(clap::Parser, Debug)]
+#[command(group(clap::ArgGroup::new("source").required(true).args(["key", "diagram"])))]
+pub struct FindCommand {
+    /// Mnemonic phrase or Master key
+    #[clap(value_name = "MNEMONIC|MASTER KEY")]
+    pub key: Option<MasterKey>,
+
+    /// Diagram file (json or toml) to find in its BIP85 wallets
+    #[clap(short = 'f', long, value_name = "FILE")]
+    pub diagram: Option<String>,
+
+    /// Target addresses to find
+    #[clap(long = "address", value_name = "ADDRESS", required = true, num_args = 1..)]
+    pub addresses: Vec<String>,
+
+    /// Account start index
+    #[clap(short, long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..65536))]
+    pub account: u32,
+
+    /// Amount of accounts to scan
+    #[clap(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..256))]
+    pub accounts: u32,
+
+    /// Address indices to scan of each chain, or of BIP85 wallets
+    #[clap(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..65536))]
+    pub gap: u32,
+
+    /// Amount of worker threads [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Password as salt
+    #[clap(hide = true, long)]
+    pub password: Option<String>,
+}
+
+impl FindCommand {
+    #[inline]
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(default_jobs)
+    }
+}
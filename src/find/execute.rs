@@ -0,0 +1,261 @@
+use super::FindCommand;
+use crate::Execute;
+use crate::derive::{DeriveMethod, MasterKey, MultisigScript};
+use crate::diagram::{DiagramFile, WORD_MAX_LENGTH, verify_fingerprint};
+use crate::utils::{inquire_password, parallel_find};
+use anyhow::anyhow;
+use artimonist::bitcoin::Address;
+use artimonist::{BIP85, Xpriv};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+type Result<T> = anyhow::Result<T>;
+
+/// Multisig layouts supported by derive command: (M, N)
+const MULTISIG: [(u8, u32); 2] = [(2, 3), (3, 5)];
+
+impl Execute for FindCommand {
+    fn execute(&mut self) -> Result<()> {
+        let targets = self
+            .addresses
+            .iter()
+            .map(|s| normalize_address(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        let (master, keys) = match (&self.key, &self.diagram) {
+            (_, Some(path)) => {
+                let content = std::fs::read_to_string(path)?;
+                let extension = std::path::Path::new(path)
+                    .extension()
+                    .and_then(|s| s.to_str());
+                let file = DiagramFile::parse(&content, extension)?
+                    .ok_or(anyhow!("Find requires a json or toml diagram file"))?;
+                let items = file.items(WORD_MAX_LENGTH)?;
+                let master = file.to_master(&items, self.password()?.as_bytes())?;
+                verify_fingerprint(&master, &file.fingerprints)?;
+                (master, None)
+            }
+            (Some(MasterKey::Xpriv(master)), _) => (*master, Some(self.account_keys(master)?)),
+            (Some(MasterKey::Mnemonic(mnemonic)), _) => {
+                let master = mnemonic.to_master(&self.password()?)?;
+                (master, Some(self.account_keys(&master)?))
+            }
+            (Some(MasterKey::Wif(_)), _) => {
+                return Err(anyhow!("Find requires a mnemonic or master key"));
+            }
+            (None, None) => return Err(anyhow!("Find requires a key or diagram file")),
+        };
+        let locations: Box<dyn Iterator<Item = Location>> = match keys {
+            Some(_) => Box::new(self.locations()),
+            None => Box::new((0..self.gap).map(|index| Location::Bip85 { index })),
+        };
+
+        // derive addresses batch by batch, the first location wins
+        let mut found = HashMap::new();
+        parallel_find(
+            locations,
+            self.jobs(),
+            |location| derive_address(&master, keys.as_ref(), location),
+            |location, addr| {
+                let addr = addr?;
+                if targets.contains(&addr) && !found.contains_key(&addr) {
+                    found.insert(addr, location);
+                }
+                Ok((found.len() == targets.len()).then_some(()))
+            },
+        )?;
+
+        let mut missing = vec![];
+        for addr in &targets {
+            match found.get(addr) {
+                Some(location) => println!("{addr}: {location}"),
+                None => missing.push(addr.as_str()),
+            }
+        }
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(anyhow!("Address not found: {}", missing.join(", "))),
+        }
+    }
+}
+
+/// Derivation location of an address
+enum Location {
+    Single {
+        method: DeriveMethod,
+        account: u32,
+        change: bool,
+        index: u32,
+    },
+    Multisig {
+        method: DeriveMethod,
+        m: u8,
+        n: u32,
+        script: MultisigScript,
+        sorted: bool,
+        account: u32,
+        index: u32,
+    },
+    Bip85 {
+        index: u32,
+    },
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Location::Single {
+                method,
+                account,
+                change,
+                index,
+            } => write!(f, "{}/{}/{index}", method.path(account), change as u8),
+            Location::Multisig {
+                method,
+                m,
+                n,
+                script,
+                sorted,
+                account,
+                index,
+            } => {
+                let paths = (account..account + n)
+                    .map(|account| format!("{}/0/{index}", method.path(account)))
+                    .collect::<Vec<_>>();
+                let script = script.to_possible_value().expect("script name");
+                write!(
+                    f,
+                    "{} (multisig {m}-{n} {}{})",
+                    paths.join(", "),
+                    script.get_name(),
+                    if sorted { "" } else { " unsorted" }
+                )
+            }
+            Location::Bip85 { index } => write!(f, "BIP85 wallet index ({index})"),
+        }
+    }
+}
+
+/// Account keys of derive methods
+type AccountKeys = HashMap<(DeriveMethod, u32), Xpriv>;
+
+impl FindCommand {
+    fn password(&self) -> Result<String> {
+        match &self.password {
+            Some(password) => Ok(password.clone()),
+            None => inquire_password(true),
+        }
+    }
+
+    /// Account keys in the scan range, multisig takes up to N accounts from the start
+    fn account_keys(&self, master: &Xpriv) -> Result<AccountKeys> {
+        let max_n = MULTISIG.iter().map(|&(_, n)| n).max().unwrap_or(1);
+        DeriveMethod::SINGLE
+            .iter()
+            .chain(DeriveMethod::MULTISIG.iter())
+            .flat_map(|&method| {
+                (self.account..self.account + self.accounts + max_n - 1)
+                    .map(move |account| (method, account))
+            })
+            .map(|(method, account)| Ok(((method, account), method.account_key(master, account)?)))
+            .collect()
+    }
+
+    /// Locations of all schemes, chains and multisig layouts in the scan range, in search order
+    fn locations(&self) -> impl Iterator<Item = Location> + use<'_> {
+        let accounts = self.account..self.account + self.accounts;
+        let gap = self.gap;
+        let single = DeriveMethod::SINGLE.into_iter().flat_map(move |method| {
+            accounts.clone().flat_map(move |account| {
+                [false, true].into_iter().flat_map(move |change| {
+                    (0..gap).map(move |index| Location::Single {
+                        method,
+                        account,
+                        change,
+                        index,
+                    })
+                })
+            })
+        });
+        let accounts = self.account..self.account + self.accounts;
+        let multisig = DeriveMethod::MULTISIG.into_iter().flat_map(move |method| {
+            let accounts = accounts.clone();
+            MULTISIG.into_iter().flat_map(move |(m, n)| {
+                let accounts = accounts.clone();
+                multisig_scripts(method).flat_map(move |(script, sorted)| {
+                    accounts.clone().flat_map(move |account| {
+                        (0..gap).map(move |index| Location::Multisig {
+                            method,
+                            m,
+                            n,
+                            script,
+                            sorted,
+                            account,
+                            index,
+                        })
+                    })
+                })
+            })
+        });
+        single.chain(multisig)
+    }
+}
+
+/// Script types and key orders of the multisig scheme, its default sorted script comes first
+fn multisig_scripts(method: DeriveMethod) -> impl Iterator<Item = (MultisigScript, bool)> {
+    let default = method.multisig_script();
+    let others = MultisigScript::value_variants()
+        .iter()
+        .copied()
+        .filter(move |&script| script != default);
+    std::iter::once(default)
+        .chain(others)
+        .flat_map(|script| [(script, true), (script, false)])
+}
+
+/// Address of the location, BIP85 wallets derive from the diagram master key
+fn derive_address(
+    master: &Xpriv,
+    keys: Option<&AccountKeys>,
+    location: &Location,
+) -> Result<String> {
+    let key = |method, account| {
+        keys.and_then(|keys| keys.get(&(method, account)))
+            .ok_or_else(|| anyhow!("Missing account key: {}", method.path(account)))
+    };
+    match *location {
+        Location::Single {
+            method,
+            account,
+            change,
+            index,
+        } => Ok(method
+            .address(key(method, account)?, change, index)?
+            .to_string()),
+        Location::Multisig {
+            method,
+            m,
+            n,
+            script,
+            sorted,
+            account,
+            index,
+        } => {
+            let accounts = (account..account + n)
+                .map(|account| key(method, account).copied())
+                .collect::<Result<Vec<_>>>()?;
+            Ok(script.wallet(m, &accounts, sorted, index)?.0)
+        }
+        Location::Bip85 { index } => Ok(master.bip85_wallet(index)?.addr),
+    }
+}
+
+/// Validate the address in current network, bech32 addresses turn to lowercase
+fn normalize_address(s: &str) -> Result<String> {
+    let address = Address::from_str(s)
+        .ok()
+        .and_then(|addr| addr.require_network(crate::derive::network()).ok())
+        .ok_or(anyhow!("Invalid address: {s}"))?;
+    Ok(address.to_string())
+}
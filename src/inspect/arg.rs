@@ -0,0 +1,6 @@
+#[derive(clap::Parser, Debug)]
+pub struct InspectCommand {
+    /// Encrypted key of bip38, standard or disguised
+    #[clap(name = "ENCRYPTED KEY")]
+    pub key: String,
+}
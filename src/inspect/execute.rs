@@ -0,0 +1,25 @@
+use super::InspectCommand;
+use crate::Execute;
+use crate::encrypt::inspect_key;
+use artimonist::bitcoin::hex::DisplayHex;
+
+impl Execute for InspectCommand {
+    fn execute(&mut self) -> anyhow::Result<()> {
+        let info = inspect_key(&self.key)?;
+        let mode = match info.ec_multiply {
+            true => "EC-multiply",
+            false => "non-EC-multiply",
+        };
+        println!("Mode: {mode}");
+        println!("Compressed: {}", info.compressed);
+        if let Some((lot, sequence)) = info.lot_sequence {
+            println!("Lot: {lot}, Sequence: {sequence}");
+        }
+        println!("Address hash: {}", info.address_hash.to_lower_hex_string());
+        println!("Disguised: {}", info.disguised);
+        if info.disguised {
+            println!("Standard key: {}", info.standard);
+        }
+        Ok(())
+    }
+}
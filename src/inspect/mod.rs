@@ -0,0 +1,4 @@
+mod arg;
+mod execute;
+
+pub use arg::InspectCommand;
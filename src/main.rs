@@ -2,6 +2,9 @@ mod bip32;
 mod derive;
 mod diagram;
 mod encrypt;
+mod find;
+mod inspect;
+mod recover;
 mod utils;
 
 use artimonist::{ComplexDiagram, SimpleDiagram};
@@ -10,6 +13,9 @@ use clap::{Parser, Subcommand};
 use derive::DeriveCommand;
 use diagram::DiagramCommand;
 use encrypt::EncryptCommand;
+use find::FindCommand;
+use inspect::InspectCommand;
+use recover::{RecoverCommand, RecoverMnemonicCommand};
 
 /// Artimonist - A tool for generating mnemonics and wallets.   
 #[derive(Parser)]
@@ -29,11 +35,18 @@ pub enum Commands {
     Encrypt(EncryptCommand<true>),
     /// Decrypt private key by bip38
     Decrypt(EncryptCommand<false>),
+    /// Inspect encrypted key of bip38, detect disguised keys
+    Inspect(InspectCommand),
     /// Derive from master key or mnemonic
     Derive(DeriveCommand),
-    /// Derive by custom bip32 path
-    #[clap(hide = true)]
+    /// Derive by custom bip32 path, ranges and wildcards
     Bip32(Bip32Command),
+    /// Recover diagram from partially remembered cells
+    Recover(RecoverCommand),
+    /// Recover damaged mnemonic of unknown, misspelled or swapped words
+    RecoverMnemonic(RecoverMnemonicCommand),
+    /// Find derivation path of wallet addresses
+    Find(FindCommand),
 }
 
 pub trait Execute {
@@ -47,8 +60,12 @@ fn main() -> anyhow::Result<()> {
         Commands::Complex(mut cmd) => cmd.execute()?,
         Commands::Encrypt(mut cmd) => cmd.execute()?,
         Commands::Decrypt(mut cmd) => cmd.execute()?,
+        Commands::Inspect(mut cmd) => cmd.execute()?,
         Commands::Derive(mut cmd) => cmd.execute()?,
         Commands::Bip32(mut cmd) => cmd.execute()?,
+        Commands::Recover(mut cmd) => cmd.execute()?,
+        Commands::RecoverMnemonic(mut cmd) => cmd.execute()?,
+        Commands::Find(mut cmd) => cmd.execute()?,
     }
     Ok(())
 }
@@ -0,0 +1,94 @@
+use artimonist::Language;
+use clap::builder::TypedValueParser;
+
+/// Printable ascii characters except space
+const DEFAULT_CHARSET: &str = r##"!"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\]^_`abcdefghijklmnopqrstuvwxyz{|}~"##;
+
+#[derive(clap::Parser, Debug)]
+#[command(group(clap::ArgGroup::new("target").args(["address", "fingerprint", "word"])))]
+pub struct RecoverCommand {
+    /// Diagram file (json or toml) with unknown or candidate cells
+    #[clap(name = "FILE")]
+    pub file: String,
+
+    /// Known wallet address of diagram wallets
+    #[clap(long)]
+    pub address: Option<String>,
+
+    /// Known master key fingerprint [default: fingerprints of diagram file]
+    #[clap(long)]
+    pub fingerprint: Option<String>,
+
+    /// Known first word of diagram mnemonic
+    #[clap(long)]
+    pub word: Option<String>,
+
+    /// Mnemonic length of the known first word
+    #[clap(long, default_value_t = 24,
+      value_parser = clap::builder::PossibleValuesParser::new(["12", "15", "18", "21", "24"])
+        .map(|s| s.parse::<u8>().unwrap()) )]
+    pub length: u8,
+
+    /// Start index of diagram wallets or mnemonics
+    #[clap(short, long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..65536))]
+    pub index: u32,
+
+    /// Amount of diagram wallets or mnemonics to check
+    #[clap(short = 'm', long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..65536))]
+    pub amount: u32,
+
+    /// Characters of unknown cells, an unknown cell can also be empty
+    #[clap(long, default_value = DEFAULT_CHARSET)]
+    pub charset: String,
+
+    /// Amount of worker threads [default: cpu cores], each one takes up to 1GB memory
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Save and resume the search progress by checkpoint file
+    #[clap(long)]
+    pub checkpoint: Option<String>,
+
+    /// Password as salt
+    #[clap(hide = true, long)]
+    pub password: Option<String>,
+
+    /// Mnemonic language
+    #[clap(hide = true, long)]
+    pub language: Option<Language>,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct RecoverMnemonicCommand {
+    /// Damaged mnemonic, `?` for unknown words, a missing word can be left out
+    #[clap(name = "MNEMONIC")]
+    pub mnemonic: String,
+
+    /// Mnemonic language [default: by the most matched words]
+    #[clap(long)]
+    pub language: Option<Language>,
+
+    /// Known wallet address of bip44/49/84/86 receive chains
+    #[clap(long, conflicts_with = "fingerprint")]
+    pub address: Option<String>,
+
+    /// Known master key fingerprint
+    #[clap(long)]
+    pub fingerprint: Option<String>,
+
+    /// Account index of the known address
+    #[clap(short, long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..65536))]
+    pub account: u32,
+
+    /// Address indices to scan of each receive chain
+    #[clap(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..65536))]
+    pub gap: u32,
+
+    /// Amount of worker threads [default: cpu cores]
+    #[clap(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Password as salt
+    #[clap(hide = true, long)]
+    pub password: Option<String>,
+}
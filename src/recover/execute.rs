@@ -0,0 +1,246 @@
+use super::RecoverCommand;
+use crate::diagram::{ComfyTable, DiagramFile, DiagramKind, WORD_MAX_LENGTH};
+use crate::utils::{
+    Progress, batch_size, default_jobs, inquire_password, parallel_find, select_language,
+};
+use crate::{Execute, utils::unicode_decode};
+use anyhow::anyhow;
+use artimonist::bitcoin::hashes::{Hash, sha256};
+use artimonist::bitcoin::secp256k1::Secp256k1;
+use artimonist::{BIP85, Diagram, Language, Xpriv};
+use std::io::{BufWriter, Write};
+
+type Result<T> = anyhow::Result<T>;
+
+impl Execute for RecoverCommand {
+    fn execute(&mut self) -> Result<()> {
+        // load diagram candidates
+        let content = std::fs::read_to_string(&self.file)?;
+        let extension = std::path::Path::new(&self.file)
+            .extension()
+            .and_then(|s| s.to_str());
+        let file = DiagramFile::parse(&content, extension)?
+            .ok_or(anyhow!("Recover requires a json or toml diagram file"))?;
+        let charset = std::iter::once(String::new())
+            .chain(unicode_decode(&self.charset).chars().map(String::from))
+            .collect::<Vec<_>>();
+        let candidates = file.candidates(WORD_MAX_LENGTH, &charset)?;
+        let total = candidates
+            .iter()
+            .try_fold(1u64, |n, vs| n.checked_mul(vs.len() as u64))
+            .ok_or(anyhow!("Too many candidates to search"))?;
+
+        // fingerprints of the diagram file are the target if no other is given
+        let fingerprints = match (&self.fingerprint, &self.address, &self.word) {
+            (Some(fingerprint), _, _) => vec![fingerprint.clone()],
+            (None, None, None) if !file.fingerprints.is_empty() => file.fingerprints.clone(),
+            (None, None, None) => {
+                return Err(anyhow!(
+                    "Recover requires --address, --fingerprint, --word or fingerprints of diagram file"
+                ));
+            }
+            _ => vec![],
+        };
+
+        // choose a mnemonic language for the first word
+        if let Some(word) = &self.word
+            && self.language.is_none()
+        {
+            self.language = Some(match Language::detect(word).as_slice() {
+                [] => return Err(anyhow!("Invalid mnemonic word: {word}")),
+                [language] => *language,
+                languages => select_language(languages)?,
+            });
+        }
+
+        // inquire the encryption password as salt
+        if self.password.is_none() {
+            self.password = Some(inquire_password(true)?);
+        }
+
+        // search candidates in batches, save checkpoint after each batch
+        let digest = self.digest(&file, &candidates, &fingerprints);
+        let start = self.load_checkpoint(total, &digest)?;
+        let jobs = self.jobs.unwrap_or_else(default_jobs);
+        let batch = batch_size(jobs) as u64;
+        let mut progress = Progress::resume(start, total);
+        let mut skipped = Skipped::default();
+        let found = parallel_find(
+            start..total,
+            jobs,
+            |&i| {
+                let items = candidate_items(&candidates, i);
+                self.check(&file, &items, &fingerprints)
+                    .map(|v| v.map(|found| (items, found)))
+            },
+            |i, result| {
+                progress.inc(1);
+                let found = match result {
+                    Ok(found) => found,
+                    // a candidate rejected by the diagram does not stop the search
+                    Err(e) => {
+                        skipped.add(i, e);
+                        None
+                    }
+                };
+                if found.is_none() && ((i + 1 - start) % batch == 0 || i + 1 == total) {
+                    self.save_checkpoint(i + 1, total, &digest)?;
+                }
+                Ok(found)
+            },
+        )?;
+        progress.finish();
+        skipped.report();
+        if let Some((items, (master, index))) = found {
+            return self.display(file.kind, &items, &master, index);
+        }
+        Err(anyhow!("No matching diagram in {total} candidates"))
+    }
+}
+
+/// Diagram items of the candidate combination at the index
+fn candidate_items(candidates: &[Vec<String>], mut index: u64) -> Vec<String> {
+    candidates
+        .iter()
+        .map(|vs| {
+            let n = vs.len() as u64;
+            let item = vs[(index % n) as usize].clone();
+            index /= n;
+            item
+        })
+        .collect()
+}
+
+type Found = (Xpriv, Option<u32>);
+
+/// Candidates rejected by the diagram, reported once after the search
+#[derive(Default)]
+struct Skipped {
+    count: u64,
+    first: Option<(u64, anyhow::Error)>,
+}
+
+impl Skipped {
+    fn add(&mut self, index: u64, e: anyhow::Error) {
+        self.count += 1;
+        self.first.get_or_insert((index, e));
+    }
+
+    fn report(&self) {
+        if let Some((index, e)) = &self.first {
+            eprintln!("Skipped candidates: {}, first: {index}, {e}", self.count);
+        }
+    }
+}
+
+impl RecoverCommand {
+    /// Check the diagram items against the known fingerprints, address or word,
+    ///   returns the master key and the matched index.
+    fn check(
+        &self,
+        file: &DiagramFile,
+        items: &[String],
+        fingerprints: &[String],
+    ) -> Result<Option<Found>> {
+        let salt = self.password.as_deref().unwrap_or_default().as_bytes();
+        let master = file.to_master(items, salt)?;
+
+        if !fingerprints.is_empty() {
+            let secp = Secp256k1::signing_only();
+            let fingerprint = master.fingerprint(&secp).to_string();
+            let matched = fingerprints
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&fingerprint));
+            return Ok(matched.then_some((master, None)));
+        }
+        for index in self.index..self.index + self.amount {
+            let matched = match (&self.address, &self.word) {
+                (Some(address), _) => master.bip85_wallet(index)?.addr == *address,
+                (_, Some(word)) => {
+                    let language = self.language.ok_or(anyhow!("unknown language"))?;
+                    let mnemonic = master.bip85_mnemonic(index, self.length as u32, language)?;
+                    mnemonic.split_whitespace().next() == Some(word.as_str())
+                }
+                _ => false,
+            };
+            if matched {
+                return Ok(Some((master, Some(index))));
+            }
+        }
+        Ok(None)
+    }
+
+    fn display(
+        &self,
+        kind: DiagramKind,
+        items: &[String],
+        master: &Xpriv,
+        index: Option<u32>,
+    ) -> Result<()> {
+        let f = &mut BufWriter::new(std::io::stdout());
+        writeln!(f)?;
+        writeln!(f, "Diagram: ")?;
+        match kind {
+            DiagramKind::Simple => {
+                writeln!(f, "{}", items.art_simple_diagram()?.0.fmt_table(false))?
+            }
+            DiagramKind::Complex => {
+                writeln!(f, "{}", items.art_complex_diagram()?.0.fmt_table(false))?
+            }
+        }
+        let secp = Secp256k1::signing_only();
+        writeln!(f, "Fingerprint: {}", master.fingerprint(&secp))?;
+        if let Some(index) = index {
+            writeln!(f, "Index: ({index})")?;
+        }
+        Ok(())
+    }
+
+    /// Digest of the diagram candidates and the target, a checkpoint only resumes the same search
+    fn digest(
+        &self,
+        file: &DiagramFile,
+        candidates: &[Vec<String>],
+        fingerprints: &[String],
+    ) -> String {
+        let search = format!(
+            "{:?} {:?} {candidates:?} {fingerprints:?} {:?} {:?} {:?} {} {} {}",
+            file.kind,
+            file.engine,
+            self.address,
+            self.word,
+            self.language,
+            self.length,
+            self.index,
+            self.amount,
+        );
+        sha256::Hash::hash(search.as_bytes()).to_string()
+    }
+
+    /// Next candidate index saved in the checkpoint file as "next/total digest"
+    fn load_checkpoint(&self, total: u64, digest: &str) -> Result<u64> {
+        let Some(path) = &self.checkpoint else {
+            return Ok(0);
+        };
+        if !std::path::Path::new(path).exists() {
+            return Ok(0);
+        }
+        let content = std::fs::read_to_string(path)?;
+        let (progress, saved) = content.trim().split_once(' ').unwrap_or_default();
+        match progress.split_once('/') {
+            Some((next, n)) if saved == digest && n.parse::<u64>() == Ok(total) => {
+                Ok(next.parse::<u64>()?.min(total))
+            }
+            _ => Err(anyhow!(
+                "Checkpoint does not match the diagram candidates or target"
+            )),
+        }
+    }
+
+    fn save_checkpoint(&self, next: u64, total: u64, digest: &str) -> Result<()> {
+        if let Some(path) = &self.checkpoint {
+            std::fs::write(path, format!("{next}/{total} {digest}\n"))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use super::RecoverMnemonicCommand;
+use crate::Execute;
+use crate::derive::{DeriveMethod, network};
+use crate::utils::{Progress, default_jobs, inquire_password, parallel_find, parallel_map};
+use anyhow::anyhow;
+use artimonist::bitcoin::Address;
+use artimonist::bitcoin::hashes::{Hash, sha256};
+use artimonist::bitcoin::secp256k1::Secp256k1;
+use artimonist::{Language, Mnemonic};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+type Result<T> = anyhow::Result<T>;
+
+/// Placeholder of unknown words
+const UNKNOWN: &str = "?";
+/// Amount of words of each language
+const WORDLIST_SIZE: usize = 2048;
+/// Candidates of a worker task to verify checksum
+const CHUNK_SIZE: u64 = 1 << 16;
+/// Limit of candidates to verify checksum
+const MAX_CANDIDATES: u64 = 1 << 32;
+
+/// Word indices of each position
+type Layout = Vec<Vec<usize>>;
+
+impl Execute for RecoverMnemonicCommand {
+    fn execute(&mut self) -> Result<()> {
+        let words = self.mnemonic.split_whitespace().collect::<Vec<_>>();
+        let languages = match self.language {
+            Some(language) => vec![language],
+            None => detect_languages(&words),
+        };
+        if languages.is_empty() {
+            return Err(anyhow!("Unknown mnemonic language"));
+        }
+        if let Some(address) = &self.address {
+            self.address = Some(
+                Address::from_str(address)
+                    .ok()
+                    .and_then(|addr| addr.require_network(network()).ok())
+                    .ok_or(anyhow!("Invalid address: {address}"))?
+                    .to_string(),
+            );
+        }
+
+        // candidates of valid checksum in all layouts
+        let jobs = self.jobs.unwrap_or_else(default_jobs).max(1);
+        let (mut total, mut mnemonics, mut seen) = (0, vec![], HashSet::new());
+        for language in languages {
+            for layout in layouts(&words, language)? {
+                let (n, valid) = valid_candidates(&layout, jobs)?;
+                total += n;
+                for entropy in valid {
+                    let mnemonic = Mnemonic::new(&entropy, language)?;
+                    if seen.insert(mnemonic.to_string()) {
+                        mnemonics.push(mnemonic);
+                    }
+                }
+            }
+        }
+        if mnemonics.is_empty() {
+            return Err(anyhow!("No valid mnemonic in {total} candidates"));
+        }
+
+        // without a known target, all valid mnemonics are listed
+        if self.address.is_none() && self.fingerprint.is_none() {
+            mnemonics.iter().for_each(|mnemonic| println!("{mnemonic}"));
+            return Ok(());
+        }
+
+        // inquire the encryption password as salt
+        if self.password.is_none() {
+            self.password = Some(inquire_password(true)?);
+        }
+
+        // check valid mnemonics batch by batch, stop at the first match
+        let mut progress = Progress::new(mnemonics.len() as u64);
+        let found = parallel_find(
+            &mnemonics,
+            jobs,
+            |mnemonic| self.check(mnemonic),
+            |mnemonic, matched| {
+                progress.inc(1);
+                Ok(matched?.then_some(mnemonic))
+            },
+        )?;
+        progress.finish();
+        if let Some(mnemonic) = found {
+            println!("{mnemonic}");
+            return Ok(());
+        }
+        Err(anyhow!(
+            "No matching mnemonic in {} valid candidates",
+            mnemonics.len()
+        ))
+    }
+}
+
+impl RecoverMnemonicCommand {
+    /// Check the mnemonic against the known fingerprint or address
+    fn check(&self, mnemonic: &Mnemonic) -> Result<bool> {
+        let salt = self.password.as_deref().unwrap_or_default();
+        let master = mnemonic.to_master(salt)?;
+
+        if let Some(fingerprint) = &self.fingerprint {
+            let secp = Secp256k1::signing_only();
+            return Ok(master
+                .fingerprint(&secp)
+                .to_string()
+                .eq_ignore_ascii_case(fingerprint));
+        }
+        if let Some(address) = &self.address {
+            for method in DeriveMethod::SINGLE {
+                let account = method.account_key(&master, self.account)?;
+                for index in 0..self.gap {
+                    if method.address(&account, false, index)?.to_string() == *address {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Languages of the most known words
+fn detect_languages(words: &[&str]) -> Vec<Language> {
+    let counts = Language::all()
+        .iter()
+        .map(|&language| {
+            let n = words.iter().filter(|w| language.contains(w)).count();
+            (language, n)
+        })
+        .collect::<Vec<_>>();
+    let max = counts.iter().map(|&(_, n)| n).max().unwrap_or(0);
+    counts
+        .into_iter()
+        .filter(|&(_, n)| n == max && n > 0)
+        .map(|(language, _)| language)
+        .collect()
+}
+
+/// Layouts of word candidates: a missing word can be at any position,
+///   adjacent words are swapped if no word is unknown.
+fn layouts(words: &[&str], language: Language) -> Result<Vec<Layout>> {
+    let layout = words
+        .iter()
+        .map(|word| word_candidates(word, language))
+        .collect::<Layout>();
+    let n = words.len();
+    if Mnemonic::VALID_SIZES.contains(&(n + 1)) {
+        return Ok((0..=n)
+            .map(|i| {
+                let mut missing = layout.clone();
+                missing.insert(i, (0..WORDLIST_SIZE).collect());
+                missing
+            })
+            .collect());
+    }
+    if !Mnemonic::VALID_SIZES.contains(&n) {
+        return Err(anyhow!("Invalid word count: {n}"));
+    }
+
+    let mut layouts = vec![layout.clone()];
+    if layout.iter().all(|vs| vs.len() < WORDLIST_SIZE) {
+        for i in 1..n {
+            let mut swapped = layout.clone();
+            swapped.swap(i - 1, i);
+            layouts.push(swapped);
+        }
+    }
+    Ok(layouts)
+}
+
+/// Word indices of a position: the word itself, its near-miss spellings,
+///   or all words if it is unknown or has no near miss.
+fn word_candidates(word: &str, language: Language) -> Vec<usize> {
+    if let Some(index) = language.index_of(word) {
+        return vec![index];
+    }
+    // short words allow one typo, longer words allow two
+    let max_distance = match word.chars().count() {
+        0..=4 => 1,
+        _ => 2,
+    };
+    let near = match word {
+        UNKNOWN => vec![],
+        _ => language
+            .wordlist()
+            .enumerate()
+            .filter(|(_, w)| edit_distance(word, w) <= max_distance)
+            .map(|(i, _)| i)
+            .collect(),
+    };
+    match near.is_empty() {
+        true => (0..WORDLIST_SIZE).collect(),
+        false => near,
+    }
+}
+
+/// Edit distance of insertions, deletions, substitutions and adjacent transpositions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    (0..=a.len()).for_each(|i| d[i][0] = i);
+    (0..=b.len()).for_each(|j| d[0][j] = j);
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Entropies of the layout candidates with valid checksum, verified on worker threads.
+///   returns the amount of candidates and the valid entropies.
+fn valid_candidates(layout: &[Vec<usize>], jobs: usize) -> Result<(u64, Vec<Vec<u8>>)> {
+    let total = layout
+        .iter()
+        .try_fold(1u64, |n, vs| n.checked_mul(vs.len() as u64))
+        .filter(|&n| n <= MAX_CANDIDATES)
+        .ok_or(anyhow!("Too many candidates to search"))?;
+    let chunks = (0..total).step_by(CHUNK_SIZE as usize).collect::<Vec<_>>();
+    let valid = parallel_map(&chunks, jobs, |&start| {
+        (start..total.min(start + CHUNK_SIZE))
+            .filter_map(|i| checksum_entropy(&candidate_indices(layout, i)))
+            .collect::<Vec<_>>()
+    });
+    Ok((total, valid.into_iter().flatten().collect()))
+}
+
+/// Word indices of the candidate combination at the index
+fn candidate_indices(layout: &[Vec<usize>], mut index: u64) -> Vec<usize> {
+    layout
+        .iter()
+        .map(|vs| {
+            let n = vs.len() as u64;
+            let item = vs[(index % n) as usize];
+            index /= n;
+            item
+        })
+        .collect()
+}
+
+/// Entropy of the word indices if the BIP39 checksum is valid
+fn checksum_entropy(indices: &[usize]) -> Option<Vec<u8>> {
+    let bits = indices.len() * 11;
+    let checksum_bits = bits / 33;
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    for (i, &index) in indices.iter().enumerate() {
+        for b in 0..11 {
+            if index >> (10 - b) & 1 == 1 {
+                let pos = i * 11 + b;
+                bytes[pos / 8] |= 0x80 >> (pos % 8);
+            }
+        }
+    }
+    let entropy = &bytes[..(bits - checksum_bits) / 8];
+    let checksum = bytes[entropy.len()] >> (8 - checksum_bits);
+    let hash = sha256::Hash::hash(entropy);
+    (hash[0] >> (8 - checksum_bits) == checksum).then(|| entropy.to_vec())
+}
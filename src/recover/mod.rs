@@ -0,0 +1,5 @@
+mod arg;
+mod execute;
+mod mnemonic;
+
+pub use arg::{RecoverCommand, RecoverMnemonicCommand};
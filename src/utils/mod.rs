@@ -1,5 +1,9 @@
 mod inquire;
+mod parallel;
+mod progress;
 mod unicode;
 
 pub use inquire::{inquire_password, select_language};
+pub use parallel::{batch_size, default_jobs, ordered_for_each, parallel_find, parallel_map};
+pub use progress::Progress;
 pub use unicode::{unicode_decode, unicode_encode};
@@ -0,0 +1,83 @@
+use std::num::NonZeroUsize;
+
+/// Default amount of worker threads
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Map items on worker threads, results keep the order of items.
+pub fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let chunk_size = items.len().div_ceil(jobs.max(1)).max(1);
+    if items.len() <= chunk_size {
+        return items.iter().map(f).collect();
+    }
+    std::thread::scope(|s| {
+        let handles = items
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+/// Amount of items of a batch for each worker thread
+const BATCH_PER_JOB: usize = 4;
+
+/// Map items on worker threads batch by batch,
+///   results are handed over to `g` in the order of items.
+pub fn ordered_for_each<T, R, F, G>(items: &[T], jobs: usize, f: F, mut g: G) -> anyhow::Result<()>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> anyhow::Result<R> + Sync,
+    G: FnMut(&T, R) -> anyhow::Result<()>,
+{
+    for batch in items.chunks(batch_size(jobs)) {
+        for (item, result) in batch.iter().zip(parallel_map(batch, jobs, &f)) {
+            g(item, result?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Amount of items of a batch for the worker threads
+pub fn batch_size(jobs: usize) -> usize {
+    jobs.max(1) * BATCH_PER_JOB
+}
+
+/// Map items on worker threads batch by batch until `g` finds a result,
+///   results are handed over to `g` in the order of items.
+pub fn parallel_find<I, R, X, F, G>(
+    items: I,
+    jobs: usize,
+    f: F,
+    mut g: G,
+) -> anyhow::Result<Option<X>>
+where
+    I: IntoIterator<Item: Sync>,
+    R: Send,
+    F: Fn(&I::Item) -> R + Sync,
+    G: FnMut(I::Item, R) -> anyhow::Result<Option<X>>,
+{
+    let mut items = items.into_iter();
+    loop {
+        let batch = items.by_ref().take(batch_size(jobs)).collect::<Vec<_>>();
+        if batch.is_empty() {
+            return Ok(None);
+        }
+        let results = parallel_map(&batch, jobs, &f);
+        for (item, result) in batch.into_iter().zip(results) {
+            if let Some(found) = g(item, result)? {
+                return Ok(Some(found));
+            }
+        }
+    }
+}
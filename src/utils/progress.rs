@@ -0,0 +1,79 @@
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+
+/// Progress bar with ETA on stderr, hidden if stderr is not a terminal.
+pub struct Progress {
+    total: u64,
+    done: u64,
+    resumed: u64,
+    start: Instant,
+    visible: bool,
+}
+
+impl Progress {
+    pub fn new(total: u64) -> Self {
+        Self::resume(0, total)
+    }
+
+    /// Progress continues from the finished amount of a previous run.
+    pub fn resume(done: u64, total: u64) -> Self {
+        let progress = Self {
+            total,
+            done,
+            resumed: done,
+            start: Instant::now(),
+            visible: std::io::stderr().is_terminal(),
+        };
+        progress.draw();
+        progress
+    }
+
+    pub fn inc(&mut self, n: u64) {
+        self.done = (self.done + n).min(self.total);
+        self.draw();
+    }
+
+    pub fn finish(&self) {
+        if self.visible {
+            eprintln!();
+        }
+    }
+
+    fn draw(&self) {
+        if !self.visible || self.total == 0 {
+            return;
+        }
+        let filled = (self.done * BAR_WIDTH as u64 / self.total) as usize;
+        let mut f = std::io::stderr();
+        let _ = write!(
+            f,
+            "\r[{}{}] {}/{} {}% ETA {}",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total,
+            self.done * 100 / self.total,
+            fmt_duration(self.eta())
+        );
+        let _ = f.flush();
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let finished = self.done - self.resumed;
+        let remaining = self.total - self.done;
+        (finished > 0).then(|| {
+            self.start
+                .elapsed()
+                .mul_f64(remaining as f64 / finished as f64)
+        })
+    }
+}
+
+fn fmt_duration(duration: Option<Duration>) -> String {
+    match duration.map(|d| d.as_secs()) {
+        Some(s) => format!("{:02}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60),
+        None => "--:--:--".to_string(),
+    }
+}
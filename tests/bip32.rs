@@ -32,4 +32,61 @@ fn test_derive_bip32() {
     const XPUB: &str = "xpub661MyMwAqRbcF3C35ZEaUF6rrrffM7xqni3X5FNZGWJHfmbcJmNdk7RxBv4ZrpexMQvL4FRv91UF7DDu8jUpVUSppS4GaXkQucWkSeWmy3R";
     let output = cli_derive!("m/0/0", XPUB);
     assert_eq!(output, include_str!("bip32/bip32_xpub"));
+
+    const TPRV: &str = "tprv8ZgxMBicQKsPdNM6e6Z5Gkn7cxFPBBGzm3339HPQC9Fnaa1YkbQ8i4UvFqf8A56AYUfNYaqppNCCZR3PE2i7ifwN5xZrU2LUngmg2gTCS45";
+    let output = cli_derive!("m/0/0", TPRV);
+    assert_eq!(output, include_str!("bip32/bip32_testnet"));
+
+    // a non-master root key has no key origin
+    const CHILD: &str = "xprv9uT3DcKTsbmsYNgLi3XUSrNoLXAetLaz77M7y9tSWHpMf3xnDNyGXqBrfSgFZsBk6xcN1H1U3p5wSg495pmfCP38ajyj3CNtkQghAuFNoCM";
+    let output = cli_derive!("m/0", CHILD);
+    assert!(output.contains("Root fingerprint: 16ac5f86\n"));
+    assert!(!output.contains("Key origin"));
+
+    // address hash of testnet, decrypts to a testnet WIF
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args([
+            "decrypt",
+            "6PYRPWotRuW8U9FqWQETbJ3KtLtBx7Kf12dZL1AA86d4m4Esme3VkTBUXu",
+        ])
+        .args(["--password", "123456"])
+        .assert()
+        .success()
+        .stdout("cVAnstxYBi258pioyHFhwiJfNx5fkfh1DgcKB54cc3nr8Uk66k4A\n");
+}
+
+#[test]
+fn test_derive_bip32_range() {
+    const MASTER: &str = "xprv9s21ZrQH143K2Z7ZyXha77A8JpqAwfEzRV7vGrxwiAmJnyGTmE4PCK7ULfVU9hhrB38bYVE4f1cQ6ZVe6pNAucfmZKMYofcRsb2FaxS8txA";
+    let output = cli_derive!("m/84'/0'/0'/{0,1}/* -i 10 -m 3", MASTER);
+    assert_eq!(output, include_str!("bip32/bip32_range"));
+
+    let output = cli_derive!("m/44'/0'/0'/0/0..=2 --show xprv,bip38,p2pkh", MASTER);
+    assert_eq!(output, include_str!("bip32/bip32_show"));
+
+    const XPUB: &str = "xpub661MyMwAqRbcF3C35ZEaUF6rrrffM7xqni3X5FNZGWJHfmbcJmNdk7RxBv4ZrpexMQvL4FRv91UF7DDu8jUpVUSppS4GaXkQucWkSeWmy3R";
+    let output = cli_derive!("m/0/0..3 --show xpub,pubkey,p2wpkh", XPUB);
+    assert_eq!(output, include_str!("bip32/bip32_xpub_range"));
+
+    let output = cli_derive!("m/86'/1'/0'/0/* -m 3 --network regtest --show p2tr", MASTER);
+    assert_eq!(output, include_str!("bip32/bip32_regtest"));
+
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args(["bip32", XPUB, "m/0/*", "--show", "bip38"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_derive_bip32_seed_wif() {
+    // BIP32 test vector 1
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    let output = cli_derive!("m/0'/1/2'", SEED);
+    assert_eq!(output, include_str!("bip32/bip32_seed"));
+
+    const WIF: &str = "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ";
+    let output = cli_derive!("", WIF);
+    assert_eq!(output, include_str!("bip32/bip32_wif"));
 }
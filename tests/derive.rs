@@ -60,6 +60,49 @@ fn test_derive_multisig() {
 
     let result = cli_derive!("--bip84 --m35 --detail -m 10", MNEMONIC);
     assert_eq!(result, include_str!("derive/bip84_m35"));
+
+    let result = cli_derive!("--bip48 --m23 --detail -m 10", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip48_m23"));
+
+    let result = cli_derive!("--bip48-nested --m35 --detail -m 10", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip48_nested_m35"));
+
+    // script type
+    let result = cli_derive!(
+        "--bip84 --m23 --script p2wsh --unsorted -m 5 --detail",
+        MNEMONIC
+    );
+    assert_eq!(result, include_str!("derive/bip84_m23_p2wsh_unsorted"));
+
+    let result = cli_derive!("--bip48 --m35 --script p2tr -m 5 --detail", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip48_m35_p2tr"));
+    // sortedmulti_a: x-only keys pushed in ascending order, "20<key>ac" then "20<key>ba"
+    for line in result.lines().filter(|s| s.starts_with("[m/0/")) {
+        let mut script = line.split(": ").nth(1).unwrap();
+        let mut keys = vec![];
+        while let Some(rest) = script.strip_prefix("20") {
+            keys.push(&rest[..64]);
+            script = &rest[66..];
+        }
+        assert_eq!(keys.len(), 5);
+        assert!(keys.is_sorted(), "unsorted x-only keys: {line}");
+    }
+
+    let result = cli_derive!(
+        "--bip44 --m23 --detail -m 10 --script p2sh --sorted",
+        MNEMONIC
+    );
+    assert_eq!(result, include_str!("derive/bip44_m23"));
+
+    // layout
+    let result = cli_derive!("--bip44 --m23 --detail -m 10 --page 10", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip44_m23"));
+
+    let result = cli_derive!("--bip84 --m23 -m 10 --page 4", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip84_m23_page"));
+
+    let result = cli_derive!("--bip84 --m23 --detail -m 10 --layout stream", MNEMONIC);
+    assert_eq!(result, include_str!("derive/bip84_m23_stream"));
 }
 
 #[test]
@@ -75,9 +118,86 @@ fn test_derive_master() {
     let result = cli_derive!("--bip84 -i 2048 -m 10 --detail", MASTER);
     assert_eq!(result, include_str!("derive/master_bip84"));
 
+    let result = cli_derive!("--bip84 -i 2048 -m 10 --detail -j 3", MASTER);
+    assert_eq!(result, include_str!("derive/master_bip84"));
+
     let result = cli_derive!("-a 1111 -i 100 -m 5 --m23 --detail", MASTER);
     assert_eq!(result, include_str!("derive/master_m23"));
 
     let result = cli_derive!("-a 1111 -i 100 -m 5 --m35 --detail", MASTER);
     assert_eq!(result, include_str!("derive/master_m35"));
+
+    // a non-master root key has no key origin
+    const CHILD: &str = "xprv9uT3DcKTsbmsYNgLi3XUSrNoLXAetLaz77M7y9tSWHpMf3xnDNyGXqBrfSgFZsBk6xcN1H1U3p5wSg495pmfCP38ajyj3CNtkQghAuFNoCM";
+    let result = cli_derive!("-m 1 --detail", CHILD);
+    assert!(result.contains("Root fingerprint: 16ac5f86\n"));
+    assert!(result.contains("\n[m/49'/0'/0']: xpub6DxvR3LQAwHL"));
+
+    let result = cli_derive!("-m 1 --m23", CHILD);
+    assert!(result.contains("Root fingerprint: 16ac5f86\n"));
+    assert!(result.contains("\n[m/49'/0'/2']: xpub6DxvR3LQAwHL"));
+}
+
+#[test]
+fn test_derive_seed_wif() {
+    const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+    let result = cli_derive!("--bip84 -m 5 --detail", SEED);
+    assert_eq!(result, include_str!("derive/seed_bip84"));
+
+    const WIF: &str = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+    let result = cli_derive!("", WIF);
+    assert_eq!(result, include_str!("derive/wif"));
+}
+
+#[test]
+fn test_derive_export() {
+    const MNEMONIC: &str =
+        "級 蠟 棒 氣 調 乏 島 陶 勞 量 強 給 電 鑽 路 婦 趙 撥 士 殿 什 遠 亞 互";
+    let coldcard = std::env::temp_dir().join("artimonist_bip48_m23.coldcard");
+    let bsms = std::env::temp_dir().join("artimonist_bip48_m23.bsms");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args(["derive", "--password", "123456", "--bip48", "--m23"])
+        .arg("--coldcard")
+        .arg(&coldcard)
+        .arg("--bsms")
+        .arg(&bsms)
+        .arg(MNEMONIC)
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&coldcard).unwrap();
+    assert_eq!(content, include_str!("derive/bip48_m23.coldcard"));
+    let content = std::fs::read_to_string(&bsms).unwrap();
+    assert_eq!(content, include_str!("derive/bip48_m23.bsms"));
+
+    let bsms = std::env::temp_dir().join("artimonist_bip48_m35_p2tr.bsms");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args(["derive", "--password", "123456", "--bip48", "--m35"])
+        .args(["--script", "p2tr", "--unsorted", "--bsms"])
+        .arg(&bsms)
+        .arg(MNEMONIC)
+        .assert()
+        .success();
+    let content = std::fs::read_to_string(&bsms).unwrap();
+    assert_eq!(content, include_str!("derive/bip48_m35_p2tr.bsms"));
+
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args([
+            "derive",
+            "--password",
+            "123456",
+            "--m35",
+            "--script",
+            "p2tr",
+        ])
+        .arg("--coldcard")
+        .arg(std::env::temp_dir().join("artimonist_p2tr.coldcard"))
+        .arg(MNEMONIC)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Error: Coldcard config does not support p2tr",
+        ));
 }
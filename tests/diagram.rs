@@ -1,6 +1,7 @@
 #![cfg(not(feature = "testnet"))]
 
 use assert_cmd::Command;
+use predicates::str::contains;
 
 macro_rules! cli_execute {
     ($args:literal) => {{
@@ -40,6 +41,9 @@ fn test_diagram_simple_v1() {
     let result = cli_execute!("simple -f simple.art --wif -i 100 -m 10 --v1");
     assert_eq!(result, include_str!("diagram/simple_wif"));
 
+    let result = cli_execute!("simple -f simple.art --wif -i 100 -m 10 --v1 --jobs 3");
+    assert_eq!(result, include_str!("diagram/simple_wif"));
+
     // xprv
     let result = cli_execute!("simple -f simple.art --xprv -m 20 --v1");
     assert_eq!(result, include_str!("diagram/simple_xprv"));
@@ -70,3 +74,152 @@ fn test_diagram_complex_v1() {
     let result = cli_execute!("complex -f complex_unicode.art --unicode -m 3 --v1");
     assert_eq!(result, include_str!("diagram/complex_unicode"));
 }
+
+#[test]
+fn test_diagram_file() {
+    let result = cli_execute!("simple -f simple.json -i 1000 -m 10");
+    assert_eq!(result, include_str!("diagram/simple_default"));
+
+    let result =
+        cli_execute!("complex -f complex.toml -i 2048 -m 5 --mnemonic 24 --wallet --master --pwd");
+    assert_eq!(result, include_str!("diagram/complex_v2"));
+}
+
+macro_rules! cli_stdin {
+    ($args:literal, $input:expr) => {{
+        let args = $args.split_whitespace().collect::<Vec<_>>();
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        let output = cmd
+            .args(&args)
+            .args(&["--password", "123456"])
+            .args(&["--language", "english"])
+            .write_stdin($input)
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        String::from_utf8(output.stdout).unwrap()
+    }};
+}
+
+#[test]
+fn test_diagram_stdin() {
+    let result = cli_stdin!(
+        "simple --stdin -i 1000 -m 10 --v1",
+        include_str!("diagram/simple.art")
+    );
+    assert_eq!(result, include_str!("diagram/simple_default"));
+
+    let result = cli_stdin!(
+        "simple -f - -i 1000 -m 10",
+        include_str!("diagram/simple.json")
+    );
+    assert_eq!(result, include_str!("diagram/simple_default"));
+}
+
+#[test]
+fn test_diagram_card() {
+    let svg = std::env::temp_dir().join("artimonist_card.svg");
+    let pdf = std::env::temp_dir().join("artimonist_card.pdf");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/diagram")
+        .args(["complex", "-f", "complex.art", "--v1", "--card", "masked"])
+        .arg("--svg")
+        .arg(&svg)
+        .arg("--pdf")
+        .arg(&pdf)
+        .args(["--password", "123456", "--language", "english"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(&svg).unwrap();
+    assert_eq!(content, include_str!("diagram/complex_masked.svg"));
+
+    let content = std::fs::read(&pdf).unwrap();
+    assert!(content.starts_with(b"%PDF-1.4"));
+    assert!(content.ends_with(b"%EOF\n"));
+    assert!(String::from_utf8_lossy(&content).contains("Fingerprint: a31d6263"));
+
+    // card only, nothing printed
+    let svg_only = std::env::temp_dir().join("artimonist_card_only.svg");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/diagram")
+        .args(["complex", "-f", "complex.art", "--v1", "--card", "masked"])
+        .arg("--card-only")
+        .arg("--svg")
+        .arg(&svg_only)
+        .args(["--password", "123456"])
+        .assert()
+        .success()
+        .stdout("");
+    let content = std::fs::read_to_string(&svg_only).unwrap();
+    assert_eq!(content, include_str!("diagram/complex_masked.svg"));
+
+    // standard pdf font has no glyphs of non-ascii cells
+    let pdf_unicode = std::env::temp_dir().join("artimonist_card_unicode.pdf");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/diagram")
+        .args([
+            "complex",
+            "-f",
+            "complex_unicode.art",
+            "--v1",
+            "--card",
+            "full",
+        ])
+        .arg("--pdf")
+        .arg(&pdf_unicode)
+        .args(["--password", "123456", "--language", "english"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "Error: Pdf card does not support non-ascii cells, export it by --svg instead",
+        ));
+    assert!(!pdf_unicode.exists());
+}
+
+macro_rules! cli_test_error {
+    ($content:expr, $($arg:literal),+) => {
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        cmd
+        .current_dir("tests/diagram")
+        .args(&[$($arg),+])
+        .args(&["--password", "123456"])
+        .args(&["--language", "english"])
+        .assert()
+        .failure()
+        .stderr(contains($content));
+    };
+}
+
+#[test]
+fn test_diagram_file_error() {
+    cli_test_error!(
+        "Error: Diagram file is Simple type, not for Complex command",
+        "complex",
+        "-f",
+        "simple.json"
+    );
+    cli_test_error!(
+        "Error: Diagram file requires engine v2, conflicts with --v1",
+        "complex",
+        "-f",
+        "complex.toml",
+        "--v1"
+    );
+    cli_test_error!(
+        "Error: Master fingerprint 4001daed does not match the diagram file",
+        "simple",
+        "-f",
+        "simple_mismatch.toml"
+    );
+    cli_test_error!(
+        "Error: Duplicate diagram cell: (1, 7)",
+        "simple",
+        "-f",
+        "simple_duplicate.toml"
+    );
+}
@@ -42,6 +42,23 @@ fn test_encrypt_mnemonic() {
     assert_eq!(original, cli_execute!("decrypt", encrypted).trim());
 }
 
+#[test]
+fn test_encrypt_verify() {
+    let encrypted = "返 曬 嫩 旱 遲 魏 橋 塔 向 緩 常 系 搬 議 駁; 庫";
+
+    let output = cli_execute!("decrypt --verify", encrypted);
+    assert!(output.contains("Original size: 15 words, (8 - (index >> 8)) * 3\n"));
+    assert!(output.ends_with("Verified: 貨 誠 仁 盈 閒 淮 非 秋 突 妹 闢 藥 展 逮 友\n"));
+
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args(["decrypt", "--verify", encrypted, "--password", "654321"])
+        .assert()
+        .failure()
+        .stdout(contains("Checksum: 222"))
+        .stderr(contains("Error: Password mismatch"));
+}
+
 #[test]
 fn test_encrypt_size() {
     let original = "館 襲 騰 動 腿 恨 彪 跨 長 圖 休 粘";
@@ -103,6 +120,228 @@ fn test_encrypt_file() {
     assert_eq!(output, include_str!("encrypt/wifs"));
 }
 
+#[test]
+fn test_encrypt_ec_multiply() {
+    // BIP38 test vectors, without and with lot and sequence
+    const TEST_DATA: &[&str] = &[
+        "TestingOneTwoThree",
+        "6PfQu77ygVyJLZjfvMLyhLMQbYnu5uguoJJ4kMCLqWwPEdfpwANVS76gTX",
+        "5K4caxezwjGCGfnoPTZ8tMcJBLB7Jvyjv4xxeacadhq8nLisLR2",
+        "MOLON LABE",
+        "6PgNBNNzDkKdhkT6uJntUXwwzQV8Rr2tZcbkDcuC9DZRsS6AtHts4Ypo1j",
+        "5JLdxTtcTHcfYcmJsNVy1v2PMDx432JPoYcBTVVRHpPaxUrdtf8",
+    ];
+    for data in TEST_DATA.chunks(3) {
+        Command::cargo_bin("artimonist")
+            .unwrap()
+            .args(["decrypt", data[1], "--password", data[0]])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", data[2]));
+    }
+
+    for lot_sequence in ["", "--lot 263183 --sequence 1"] {
+        let intermediate = Command::cargo_bin("artimonist")
+            .unwrap()
+            .args(["encrypt", "--intermediate", "--password", "123456"])
+            .args(lot_sequence.split_whitespace())
+            .output()
+            .unwrap();
+        let intermediate = String::from_utf8(intermediate.stdout).unwrap();
+        assert!(intermediate.starts_with("passphrase"));
+
+        // generate keys without password, verify them by password
+        let output = cli_execute!("encrypt -m 2", intermediate.trim());
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let [address, key, confirmation] = line.split(", ").collect::<Vec<_>>()[..] else {
+                panic!("invalid line: {line}");
+            };
+            assert!(key.starts_with("6P"));
+            assert!(confirmation.starts_with("cfrm38"));
+            assert_eq!(address, cli_execute!("decrypt", confirmation).trim());
+
+            let wif = cli_execute!("decrypt", key);
+            let output = Command::cargo_bin("artimonist")
+                .unwrap()
+                .args(["bip32", "--password", "123456", wif.trim()])
+                .output()
+                .unwrap();
+            let output = String::from_utf8(output.stdout).unwrap();
+            assert!(output.contains(&format!("P2PKH: {address}")));
+        }
+    }
+}
+
+#[test]
+fn test_encrypt_disguise() {
+    let original = "KyyXeMvCn36KuedmVX727NYQ35YEeF4z1ZjXGyqgFpmZM4AcY8ay";
+    let disguised = "6PbgJLuKokN13a8giAFj48nkbBF3ZKBPYHLY1Vfafm5gGj37EhagVLdDWq";
+
+    assert_eq!(
+        disguised,
+        cli_execute!("encrypt --disguise", original).trim()
+    );
+    assert_eq!(original, cli_execute!("decrypt", disguised).trim());
+}
+
+#[test]
+fn test_encrypt_recover() {
+    const KEY: &str = "6PYPVwvgux4mN96iwj1RGvbiGmmPWpkiQimpkP1fvFGGhT38XxZed6Kdth";
+    const OUTPUT: &str =
+        "Password: 123456\nPrivate key: KyyXeMvCn36KuedmVX727NYQ35YEeF4z1ZjXGyqgFpmZM4AcY8ay\n";
+
+    for candidates in [
+        ["--guess", "123465"],
+        ["--wordlist", "passwords"],
+        ["--wordlist", "passwords_crlf"],
+    ] {
+        Command::cargo_bin("artimonist")
+            .unwrap()
+            .current_dir("tests/encrypt")
+            .args(["decrypt", KEY, "--recover"])
+            .args(candidates)
+            .assert()
+            .success()
+            .stdout(OUTPUT);
+    }
+}
+
+#[test]
+fn test_encrypt_testnet_key() {
+    const TEST_DATA: &[&str] = &[
+        // compression
+        "cQLX7Gv4D6nb5672svv9Uh3TfJqeJhAg5bszPQJBkwRZboGgM93Y",
+        "6PYKj3EB2Pmamtz9AsNN4NTNvSPGuaTvGEH4PkgDnRxpXQ3SsKJVVV4D1t",
+        // no compression
+        "938jwjergAxARSWx2YSt9nSBWBz24h8gLhv7EUfgEP1wpMLg6iX",
+        "6PRL8jj6ckzTjR99M8hZa2pwr1iMbgdihszJegoQEiErPE8nrYyZ3Ti887",
+    ];
+
+    for data in TEST_DATA.chunks(2) {
+        let output = cli_execute!("encrypt", data[0]);
+        assert_eq!(output.trim(), data[1]);
+
+        let decrypted = cli_execute!("decrypt", data[1]);
+        assert_eq!(decrypted.trim(), data[0]);
+    }
+}
+
+#[test]
+fn test_encrypt_confirmation() {
+    // BIP38 test vectors
+    const TEST_DATA: &[&str] = &[
+        "MOLON LABE",
+        "cfrm38V8aXBn7JWA1ESmFMUn6erxeBGZGAxJPY4e36S9QWkzZKtaVqLNMgnifETYw7BPwWC9aPD",
+        "1Jscj8ALrYu2y9TD8NrpvDBugPedmbj4Yh",
+        "ΜΟΛΩΝ ΛΑΒΕ",
+        "cfrm38V8G4qq2ywYEFfWLD5Cc6msj9UwsG2Mj4Z6QdGJAFQpdatZLavkgRd1i4iBMdRngDqDs51",
+        "1Lurmih3KruL4xDB5FmHof38yawNtP9oGf",
+    ];
+    for data in TEST_DATA.chunks(3) {
+        Command::cargo_bin("artimonist")
+            .unwrap()
+            .args(["decrypt", data[1], "--password", data[0]])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", data[2]));
+    }
+}
+
+#[test]
+fn test_encrypt_report() {
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/encrypt")
+        .args(["decrypt", "mixed", "--password", "123456"])
+        .assert()
+        .success()
+        .stdout(include_str!("encrypt/mixed.out"))
+        .stderr(contains("Converted: 2, Skipped: 2, Failed: 1"))
+        .stderr(contains("Skipped lines: 3, 4"))
+        .stderr(contains("Failed lines: 5"));
+
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/encrypt")
+        .args(["decrypt", "mixed", "--strict", "--password", "123456"])
+        .assert()
+        .failure()
+        .stderr(contains("1 keys failed to convert"));
+
+    let output = std::env::temp_dir().join("artimonist_mixed.out");
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/encrypt")
+        .args(["decrypt", "mixed", "--password", "123456", "-o"])
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout("");
+    let content = std::fs::read_to_string(&output).unwrap();
+    assert_eq!(content, include_str!("encrypt/mixed.out"));
+}
+
+#[test]
+fn test_encrypt_jobs() {
+    for jobs in ["1", "3"] {
+        Command::cargo_bin("artimonist")
+            .unwrap()
+            .current_dir("tests/encrypt")
+            .args(["decrypt", "mixed", "--password", "123456", "-j", jobs])
+            .assert()
+            .success()
+            .stdout(include_str!("encrypt/mixed.out"))
+            .stderr(contains("Skipped lines: 3, 4"))
+            .stderr(contains("Failed lines: 5"));
+    }
+}
+
+#[test]
+fn test_encrypt_format() {
+    let output = cli_execute!("encrypt keys.csv --format csv --column key");
+    assert_eq!(output, include_str!("encrypt/keys.csv.out"));
+    let output = cli_execute!("decrypt keys.csv.out --format csv --column key");
+    assert_eq!(output, include_str!("encrypt/keys.csv"));
+
+    let output = cli_execute!("encrypt keys.tsv --format tsv --column 2");
+    assert_eq!(output, include_str!("encrypt/keys.tsv.out"));
+
+    let output = cli_execute!("encrypt keys.jsonl --format jsonl --field key");
+    assert_eq!(output, include_str!("encrypt/keys.jsonl.out"));
+    let output = cli_execute!("decrypt keys.jsonl.out --format jsonl --field key");
+    assert_eq!(output, include_str!("encrypt/keys.jsonl"));
+}
+
+#[test]
+fn test_encrypt_mnemonic_file() {
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .current_dir("tests/encrypt")
+        .args(["encrypt", "mnemonics", "--password", "123456"])
+        .assert()
+        .success()
+        .stdout(include_str!("encrypt/mnemonics.out"))
+        .stderr(contains("Converted: 2, Skipped: 1, Failed: 0"))
+        .stderr(contains("Skipped lines: 4"));
+
+    let output = cli_execute!("decrypt mnemonics.out");
+    let expected = include_str!("encrypt/mnemonics").replace(
+        "返 曬 嫩 旱 遲 魏 橋 塔 向 緩 常 系 搬 議 駁; 庫",
+        "貨 誠 仁 盈 閒 淮 非 秋 突 妹 闢 藥 展 逮 友",
+    );
+    assert_eq!(output, expected);
+
+    // `; N` is the desired word count of the decrypted mnemonic
+    let output = cli_execute!("decrypt mnemonics_count");
+    assert_eq!(
+        output,
+        "old: 貨 誠 仁 盈 閒 淮 非 秋 突 妹 闢 藥 展 逮 友\n\
+         股 珍 職 鋪 截 席 卡 藍 忙 糊 數 繪 伏 充 啦 針 態 高 貝 炸 版 賞 鉛 減\n"
+    );
+}
+
 macro_rules! cli_test_error {
     ($content:expr, $($arg:literal),+) => {
         let mut cmd = Command::cargo_bin("artimonist").unwrap();
@@ -132,4 +371,34 @@ fn test_encrypt_error() {
         "decrypt",
         "6PYPVwvgux4mN96iwj1RGvbiGmmPWpkiQimpkP1fvFGGhT38XxZed6Kdt1"
     );
+    cli_test_error!(
+        "Error: Column not found: secret",
+        "encrypt",
+        "tests/encrypt/keys.csv",
+        "--format",
+        "csv",
+        "--column",
+        "secret"
+    );
+    cli_test_error!(
+        "Error: Invalid password",
+        "decrypt",
+        "cfrm38V8aXBn7JWA1ESmFMUn6erxeBGZGAxJPY4e36S9QWkzZKtaVqLNMgnifETYw7BPwWC9aPD"
+    );
+    cli_test_error!(
+        "Error: Amount is only for generating keys from intermediate code",
+        "encrypt",
+        "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn",
+        "-m",
+        "2"
+    );
+    cli_test_error!(
+        "5 is not in 100000..=999999",
+        "encrypt",
+        "--intermediate",
+        "--lot",
+        "5",
+        "--sequence",
+        "1"
+    );
 }
@@ -0,0 +1,102 @@
+#![cfg(not(feature = "testnet"))]
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+macro_rules! cli_execute {
+    ($args:literal) => {{
+        let args = $args.split_whitespace().collect::<Vec<_>>();
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        let output = cmd
+            .current_dir("tests/find")
+            .arg("find")
+            .args(&args)
+            .args(&["--password", "123456"])
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        String::from_utf8(output.stdout).unwrap()
+    }};
+}
+
+const MASTER: &str = "xprv9s21ZrQH143K4UoTfggaDMmCkfpe9UoALJsg38fDuE5aEmiP9eub61MJmkMfKVjRdM38StnFGo3nb4tGgXZ91LeZZFsG11u7paJzCk9memZ";
+
+#[test]
+fn test_find_master() {
+    let result = cli_execute!(
+        "xprv9s21ZrQH143K4UoTfggaDMmCkfpe9UoALJsg38fDuE5aEmiP9eub61MJmkMfKVjRdM38StnFGo3nb4tGgXZ91LeZZFsG11u7paJzCk9memZ --accounts 3 --address BC1Q9A2JVFFMKW27D3W0QTS7AMFFZK795S7MMSR8JN 13ajqCCTtoxG2yCdTibWkX4ax2XVc42w7F 36PhT9RvXyivX3yN13unJaqBSvoH5n3rub 3FGwFUV3opaDLkjTyLW9NoWFivVPakGbCZ bc1qpa20l5qkkc4g2v96mw5w3kvzukclag2z2r2dcuga9afhwgf65srspqhc8f"
+    );
+    assert_eq!(result, include_str!("find/master"));
+
+    // bip86 test vector
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.args(["find", MNEMONIC, "--password", ""])
+        .args([
+            "--address",
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr: m/86'/0'/0'/0/0\n",
+        );
+}
+
+#[test]
+fn test_find_multisig_script() {
+    const MNEMONIC: &str =
+        "級 蠟 棒 氣 調 乏 島 陶 勞 量 強 給 電 鑽 路 婦 趙 撥 士 殿 什 遠 亞 互";
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.args(["find", MNEMONIC, "--password", "123456", "--address"])
+        .args([
+            "bc1q00kq4pemwt8tqfz3ekhz63nu8cllym76kv8m4an3na92986gu9mqs39m3a",
+            "bc1pqtc6psztlp0hpvwuwdm5l0lzuzuydu7tqny4al40jqu7zf0s3fwq56cawz",
+        ])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "bc1q00kq4pemwt8tqfz3ekhz63nu8cllym76kv8m4an3na92986gu9mqs39m3a: ",
+            "m/84'/0'/0'/0/3, m/84'/0'/1'/0/3, m/84'/0'/2'/0/3 (multisig 2-3 p2wsh unsorted)\n",
+            "bc1pqtc6psztlp0hpvwuwdm5l0lzuzuydu7tqny4al40jqu7zf0s3fwq56cawz: ",
+            "m/48'/0'/0'/2'/0/1, m/48'/0'/1'/2'/0/1, m/48'/0'/2'/2'/0/1, ",
+            "m/48'/0'/3'/2'/0/1, m/48'/0'/4'/2'/0/1 (multisig 3-5 p2tr)\n",
+        ));
+}
+
+#[test]
+fn test_find_diagram() {
+    let result = cli_execute!("-f simple.json --address 3Hre5sQvitbSVVPpoUBH5pnCE8AHrUDNL4");
+    assert_eq!(
+        result,
+        "3Hre5sQvitbSVVPpoUBH5pnCE8AHrUDNL4: BIP85 wallet index (2)\n"
+    );
+}
+
+#[test]
+fn test_find_error() {
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.args(["find", MASTER, "--gap", "5"])
+        .args(["--address", "3Gh1a1utjvpao5qjobgcZ4c1XDP6nLo4RN"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "Error: Address not found: 3Gh1a1utjvpao5qjobgcZ4c1XDP6nLo4RN",
+        ));
+
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.args(["find", MASTER, "--address", "tb1qxyz"])
+        .assert()
+        .failure()
+        .stderr(contains("Error: Invalid address: tb1qxyz"));
+
+    // wrong password of diagram file
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.current_dir("tests/find")
+        .args(["find", "-f", "simple.json", "--password", "654321"])
+        .args(["--address", "3Hre5sQvitbSVVPpoUBH5pnCE8AHrUDNL4"])
+        .assert()
+        .failure()
+        .stderr(contains("does not match the diagram file"));
+}
@@ -0,0 +1,36 @@
+use assert_cmd::Command;
+use predicates::str::contains;
+
+#[test]
+fn test_inspect_key() {
+    // disguised keys show their standard keys
+    const TEST_DATA: &[&str] = &[
+        "6PbgJLuKokN13a8giAFj48nkbBF3ZKBPYHLY1Vfafm5gGj37EhagVLdDWq",
+        "Mode: non-EC-multiply\nCompressed: true\nAddress hash: 5b79e03e\nDisguised: true\n\
+         Standard key: 6PYPVwvgux4mN96iwj1RGvbiGmmPWpkiQimpkP1fvFGGhT38XxZed6Kdth\n",
+        "6PUnJsaPthDRfjZH2fAhsmxLiRevGjVN9RRrSLKqohtSAW4sBPaYoAzHk9",
+        "Mode: non-EC-multiply\nCompressed: false\nAddress hash: e957a24a\nDisguised: true\n\
+         Standard key: 6PRVWUbkztvBzJXKGDvQ6ZmJQ2BGEF4h1rs9BDfw4C52bE4tUeZWzZ6Qwp\n",
+        "6PgNBNNzDkKdhkT6uJntUXwwzQV8Rr2tZcbkDcuC9DZRsS6AtHts4Ypo1j",
+        "Mode: EC-multiply\nCompressed: false\nLot: 263183, Sequence: 1\n\
+         Address hash: bb458cef\nDisguised: false\n",
+    ];
+    for data in TEST_DATA.chunks(2) {
+        Command::cargo_bin("artimonist")
+            .unwrap()
+            .args(["inspect", data[0]])
+            .assert()
+            .success()
+            .stdout(data[1]);
+    }
+
+    Command::cargo_bin("artimonist")
+        .unwrap()
+        .args([
+            "inspect",
+            "KyyXeMvCn36KuedmVX727NYQ35YEeF4z1ZjXGyqgFpmZM4AcY8ay",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid encrypted key"));
+}
@@ -0,0 +1,92 @@
+#![cfg(not(feature = "testnet"))]
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+macro_rules! cli_execute {
+    ($args:literal) => {{
+        let args = $args.split_whitespace().collect::<Vec<_>>();
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        let output = cmd
+            .current_dir("tests/recover")
+            .arg("recover")
+            .args(&args)
+            .args(&["--password", "123456"])
+            .args(&["--language", "english"])
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        String::from_utf8(output.stdout).unwrap()
+    }};
+}
+
+#[test]
+fn test_recover_diagram() {
+    let result = cli_execute!("simple.toml --fingerprint 4001daed");
+    assert_eq!(result, include_str!("recover/simple_fingerprint"));
+
+    let result =
+        cli_execute!("simple.toml --address 3MqiYB6domzaqzd46b2EX3Z5bzKAJn2ysa -i 98 -m 3");
+    assert_eq!(result, include_str!("recover/simple_address"));
+
+    let result = cli_execute!("simple.toml --word dutch -i 1000");
+    assert!(result.ends_with("Index: (1000)\n"));
+
+    // fingerprints of the diagram file as the target
+    let result = cli_execute!("simple_fingerprints.toml");
+    assert_eq!(result, include_str!("recover/simple_fingerprint"));
+}
+
+#[test]
+fn test_recover_checkpoint() {
+    let checkpoint = std::env::temp_dir().join("artimonist_recover_checkpoint");
+    let _ = std::fs::remove_file(&checkpoint);
+    let recover = |fingerprint: &str| {
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        cmd.current_dir("tests/recover")
+            .args(["recover", "simple.toml", "--fingerprint", fingerprint])
+            .arg("--checkpoint")
+            .arg(&checkpoint)
+            .args(["--password", "123456"])
+            .assert()
+            .failure()
+    };
+
+    // a finished search resumes at the end
+    recover("00000000").stderr(contains("Error: No matching diagram in 8 candidates"));
+    let content = std::fs::read_to_string(&checkpoint).unwrap();
+    assert!(content.starts_with("8/8 "));
+    recover("00000000").stderr(contains("Error: No matching diagram in 8 candidates"));
+
+    // another target or a checkpoint without digest does not resume
+    const MISMATCH: &str = "Error: Checkpoint does not match the diagram candidates or target";
+    recover("4001daed").stderr(contains(MISMATCH));
+    std::fs::write(&checkpoint, "8/8\n").unwrap();
+    recover("4001daed").stderr(contains(MISMATCH));
+}
+
+#[test]
+fn test_recover_mnemonic() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const ADDRESS: &str = "3PhKNpnZZ4RwCPaXpSyk67nDxyoU7jfSTP";
+    for damaged in [
+        "abandon abandon abandon abandon abandn abandon abandon abandon abandon abandon abandon about",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about abandon",
+        "abandon abandon abandon abandon ? abandon abandon abandon abandon abandon abandon about",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    ] {
+        let mut cmd = Command::cargo_bin("artimonist").unwrap();
+        cmd.args(["recover-mnemonic", damaged, "--address", ADDRESS])
+            .args(["--password", "123456"])
+            .assert()
+            .success()
+            .stdout(format!("{MNEMONIC}\n"));
+    }
+
+    let mut cmd = Command::cargo_bin("artimonist").unwrap();
+    cmd.args(["recover-mnemonic", "abandon abandon abandon about"])
+        .assert()
+        .failure()
+        .stderr(contains("Error: Invalid word count: 4"));
+}